@@ -1,16 +1,26 @@
+use crate::config;
+use crate::error::{CfLvlError, EXIT_FAILURE, EXIT_NOT_FOUND};
+use crate::utils::{
+    ask_accept, display_path, fetch_json, fetch_json_cached, fetch_status, is_json, is_quiet,
+    open_url, print_json, render_format, report_timings, sanitize_filename, warn, write_starter,
+    ContestWindow, Rng, Spinner,
+};
 use reqwest::blocking::Client;
 use serde::Deserialize;
 use std::collections::HashSet;
 use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
 use std::thread;
 use std::time::Duration;
 
-const ATCODER_HANDLE: &str = "Exonerate";
 const API_THROTTLE: Duration = Duration::from_secs(1);
+const MAX_TASK_POSITION: u32 = 8;
 
 #[derive(Debug, Deserialize, Clone)]
 struct AtcoderContest {
     id: String,
+    start_epoch_second: u64,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -18,6 +28,23 @@ struct AtcoderProblem {
     id: String,
     #[serde(rename = "contest_id")]
     contest_id: String,
+    /// Full title such as `A. Pair`; some entries only have the bare `name`.
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    name: Option<String>,
+}
+
+impl AtcoderProblem {
+    /// Human-readable title, safe for file names, falling back to the task id.
+    fn display_title(&self) -> String {
+        self.title
+            .as_deref()
+            .or(self.name.as_deref())
+            .map(sanitize_filename)
+            .filter(|title| title != "problem")
+            .unwrap_or_else(|| self.id.clone())
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -29,12 +56,37 @@ struct AtcoderSubmission {
     epoch_second: u64,
 }
 
-pub fn run(client: &Client, index_input: &str) -> Result<(), Box<dyn Error>> {
+/// Filters and output settings for the AtCoder picker.
+#[derive(Debug, Default)]
+pub struct PickOptions {
+    pub window: ContestWindow,
+    /// Keep the solved set gathered so far when a submissions page after the first fails.
+    pub allow_partial: bool,
+    /// Template from `--format` replacing the default message.
+    pub format: Option<String>,
+    /// Pick a random unsolved task instead of the newest.
+    pub random: bool,
+    /// Show the pick without creating a file or opening anything.
+    pub dry_run: bool,
+    /// Skip the starter file but still open the task.
+    pub no_file: bool,
+    /// Propose tasks one at a time and wait for y/n before opening one.
+    pub confirm: bool,
+    /// Seed for `random`, so a pick can be repeated.
+    pub seed: Option<u64>,
+}
+
+/// Picks the newest (or, with `random`, any) unsolved ABC task with the given index.
+pub fn run(
+    client: &Client,
+    index_input: &str,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
     let task_letter = normalize_index(index_input)?;
 
-    let abc_contests = fetch_abc_contests(client)?;
+    let abc_contests = fetch_abc_contests(client, &options.window)?;
     let problems = fetch_problems(client)?;
-    let solved = fetch_user_submissions(client)?;
+    let solved = fetch_user_submissions(client, options.allow_partial)?;
 
     let mut candidates: Vec<AtcoderProblem> = problems
         .into_iter()
@@ -52,31 +104,242 @@ pub fn run(client: &Client, index_input: &str) -> Result<(), Box<dyn Error>> {
             .then_with(|| a.id.cmp(&b.id))
     });
 
-    if let Some(problem) = candidates
-        .into_iter()
-        .find(|problem| !solved.contains(&problem.id))
-    {
-        let url = format!(
-            "https://atcoder.jp/contests/{}/tasks/{}",
-            problem.contest_id, problem.id
+    candidates.retain(|problem| !solved.contains(&problem.id));
+    if options.dry_run && !is_quiet() && !is_json() {
+        println!(
+            "{} candidate(s) at task '{}'.",
+            candidates.len(),
+            task_letter
         );
-
-        if webbrowser::open(&url).is_ok() {
-            println!(
-                "Opening AtCoder ABC contest {} task {}",
-                problem.contest_id, problem.id
-            );
+    }
+    let mut rng = options.seed.map_or_else(Rng::from_time, Rng::seeded);
+    let mut rejected = 0;
+    let pick = loop {
+        if candidates.is_empty() {
+            break None;
+        }
+        let i = if options.random {
+            rng.below(candidates.len())
         } else {
-            println!("Failed to open problem");
+            0
+        };
+        if !options.confirm {
+            break Some(candidates.swap_remove(i));
         }
+        // Rejected tasks leave the pool so the next proposal is a new one
+        let problem = candidates.remove(i);
+        eprintln!(
+            "{} ({}), {} left",
+            problem.display_title(),
+            problem.contest_id,
+            candidates.len() + 1
+        );
+        match ask_accept("Solve this one?")? {
+            Some(true) => break Some(problem),
+            Some(false) => rejected += 1,
+            None => return Ok(()),
+        }
+    };
+    if pick.is_none() && rejected > 0 {
+        eprintln!("No tasks left after {rejected} rejection(s).");
+        return Ok(());
+    }
+    let Some(problem) = pick else {
+        if !is_quiet() {
+            eprintln!(
+                "No unsolved AtCoder ABC '{}' problem found.",
+                task_letter.to_ascii_uppercase()
+            );
+        }
+        report_timings();
+        std::process::exit(EXIT_NOT_FOUND);
+    };
+    open_task(&problem, options);
+
+    Ok(())
+}
+
+/// Checks `handle` against its AtCoder profile page, which is a 404 for unknown users.
+pub fn user_exists(client: &Client, handle: &str) -> Result<bool, Box<dyn Error>> {
+    let url = format!("https://atcoder.jp/users/{handle}");
+    let _spinner = Spinner::start("Checking handle…");
+    match fetch_status(client, &url)? {
+        404 => Ok(false),
+        status if (200..300).contains(&status) => Ok(true),
+        status => Err(format!("unexpected HTTP {status} from {url}").into()),
+    }
+}
+
+/// Opens `handle`'s AtCoder profile in the browser.
+pub fn run_me(handle: &str) -> Result<(), Box<dyn Error>> {
+    let url = format!("https://atcoder.jp/users/{handle}");
+    if is_json() {
+        return print_json(&serde_json::json!({ "url": url }));
+    }
+    if open_url(&url) {
+        println!("Opening {url}");
+    } else {
+        println!("{url}");
+    }
+    Ok(())
+}
+
+/// Opens a task given by contest and task id, e.g. from a pasted URL.
+pub fn run_task(
+    client: &Client,
+    contest_id: &str,
+    task_id: &str,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let problem = fetch_problems(client)?
+        .into_iter()
+        .find(|problem| problem.contest_id == contest_id && problem.id == task_id)
+        .ok_or_else(|| CfLvlError::NotFound {
+            message: format!("AtCoder task {task_id} not found in contest {contest_id}."),
+        })?;
+    open_task(&problem, options);
+    Ok(())
+}
+
+/// Creates the starter file when `atcoder.dir` is set and opens the task in the
+/// browser. `format` (from `--format`) replaces the default message; AtCoder has
+/// no ratings, so `{rating}` stays empty.
+fn open_task(problem: &AtcoderProblem, options: &PickOptions) {
+    let format = options.format.as_deref();
+    let url = format!(
+        "https://atcoder.jp/contests/{}/tasks/{}",
+        problem.contest_id, problem.id
+    );
+    let stub = if options.no_file {
+        Ok(None)
     } else {
+        create_stub(problem, options.dry_run)
+    };
+    let file_info = match stub {
+        Ok(file_info) => file_info,
+        Err(err) => {
+            warn(format_args!("could not create starter file: {}", err));
+            None
+        }
+    };
+
+    // Scripts and editor plugins open things themselves
+    if is_json() {
+        let record = serde_json::json!({
+            "platform": "atcoder",
+            "id": problem.id,
+            "contest_id": problem.contest_id,
+            "index": problem_index(&problem.id),
+            "name": problem.display_title(),
+            "url": url,
+            "path": file_info.as_ref().map(|(path, _)| {
+                fs::canonicalize(path)
+                    .unwrap_or_else(|_| path.clone())
+                    .display()
+                    .to_string()
+            }),
+            "created": file_info.as_ref().map(|(_, created)| *created),
+        });
+        if let Err(err) = print_json(&record) {
+            eprintln!("Error: {}", err);
+            std::process::exit(EXIT_FAILURE);
+        }
+        return;
+    }
+
+    if let Some(format) = format {
+        let index = problem_index(&problem.id).unwrap_or_default();
         println!(
-            "No unsolved AtCoder ABC '{}' problem found.",
-            task_letter.to_ascii_uppercase()
+            "{}",
+            render_format(
+                format,
+                &[
+                    ("{name}", problem.display_title()),
+                    ("{url}", url.clone()),
+                    ("{contest}", problem.contest_id.clone()),
+                    ("{index}", index),
+                    (
+                        "{path}",
+                        file_info
+                            .as_ref()
+                            .map(|(path, _)| display_path(path))
+                            .unwrap_or_default(),
+                    ),
+                ],
+            )
         );
+        if !options.dry_run {
+            open_url(&url);
+        }
+    } else if is_quiet() {
+        if !options.dry_run {
+            open_url(&url);
+        }
+        // Just the most useful value: the starter file, else the URL
+        match &file_info {
+            Some((path, _)) => println!("{}", display_path(path)),
+            None => println!("{url}"),
+        }
+    } else if !options.dry_run && open_url(&url) {
+        println!(
+            "Opening AtCoder ABC contest {} task {}: {}",
+            problem.contest_id,
+            problem.id,
+            problem.display_title()
+        );
+    } else {
+        println!(
+            "AtCoder ABC contest {} task {}: {}",
+            problem.contest_id,
+            problem.id,
+            problem.display_title()
+        );
+        println!("URL: {url}");
+    }
+    if let (None, false, Some((path, created))) = (format, is_quiet(), &file_info) {
+        let status = match (*created, options.dry_run) {
+            (true, true) => "would create",
+            (true, false) => "Created",
+            (false, _) => "Exists",
+        };
+        println!("File: {} ({})", display_path(path), status);
     }
+    if options.dry_run && !is_quiet() && !is_json() && format.is_none() {
+        println!("Dry run: no file written, nothing opened.");
+    }
+}
 
-    Ok(())
+/// Writes `<atcoder.dir>/<title>.cpp` if the directory is configured. Returns the
+/// path and whether it was created; with `dry_run`, whether it would be.
+fn create_stub(
+    problem: &AtcoderProblem,
+    dry_run: bool,
+) -> Result<Option<(PathBuf, bool)>, Box<dyn Error>> {
+    let Some(dir) = &config::settings()?.atcoder_dir.value else {
+        return Ok(None);
+    };
+    let path = dir.join(format!("{}.cpp", problem.display_title()));
+    if dry_run {
+        let missing = !path.exists();
+        return Ok(Some((path, missing)));
+    }
+    let starter = r#"#include <iostream>
+
+int main() {
+}
+"#;
+    let created = write_starter(&path, starter)?;
+    Ok(Some((path, created)))
+}
+
+/// Maps a 1-based task position to its index letter (`5` -> `e`).
+pub fn position_to_index(position: u32) -> Result<String, String> {
+    if !(1..=MAX_TASK_POSITION).contains(&position) {
+        return Err(format!(
+            "Task position must be between 1 and {MAX_TASK_POSITION}."
+        ));
+    }
+    Ok(char::from(b'a' + (position - 1) as u8).to_string())
 }
 
 fn normalize_index(input: &str) -> Result<String, Box<dyn Error>> {
@@ -89,38 +352,98 @@ fn normalize_index(input: &str) -> Result<String, Box<dyn Error>> {
     Ok(trimmed)
 }
 
-fn fetch_abc_contests(client: &Client) -> Result<HashSet<String>, Box<dyn Error>> {
+fn fetch_abc_contests(
+    client: &Client,
+    window: &ContestWindow,
+) -> Result<HashSet<String>, Box<dyn Error>> {
     let url = "https://kenkoooo.com/atcoder/resources/contests.json";
+    let _spinner = Spinner::start("Fetching contests…");
     throttle();
-    let contests: Vec<AtcoderContest> = client.get(url).send()?.json()?;
+    let contests: Vec<AtcoderContest> = fetch_json_cached(client, url)?;
+    if contests.is_empty() {
+        return Err(CfLvlError::EmptyResponse {
+            what: "contest list",
+        }
+        .into());
+    }
 
     Ok(contests
         .into_iter()
         .filter(|contest| contest.id.to_lowercase().starts_with("abc"))
+        .filter(|contest| window.contains(contest.start_epoch_second))
         .map(|contest| contest.id)
         .collect())
 }
 
 fn fetch_problems(client: &Client) -> Result<Vec<AtcoderProblem>, Box<dyn Error>> {
     let url = "https://kenkoooo.com/atcoder/resources/problems.json";
+    let _spinner = Spinner::start("Fetching problems…");
     throttle();
-    let problems: Vec<AtcoderProblem> = client.get(url).send()?.json()?;
+    let problems: Vec<AtcoderProblem> = fetch_json_cached(client, url)?;
+    if problems.is_empty() {
+        return Err(CfLvlError::EmptyResponse {
+            what: "problem list",
+        }
+        .into());
+    }
     Ok(problems)
 }
 
-fn fetch_user_submissions(client: &Client) -> Result<HashSet<String>, Box<dyn Error>> {
-    let mut from_second: u64 = 0;
-    let handle = ATCODER_HANDLE.to_ascii_lowercase();
-    let mut accepted = HashSet::new();
-
-    loop {
+fn fetch_user_submissions(
+    client: &Client,
+    allow_partial: bool,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+    let handle = config::settings()?.atcoder_handle()?.to_ascii_lowercase();
+    let accepted = collect_accepted(allow_partial, |page, from_second| {
         let url = format!(
             "https://kenkoooo.com/atcoder/atcoder-api/v3/user/submissions?user={}&from_second={}",
             handle, from_second
         );
-
+        let _spinner = Spinner::start(format!("Fetching submissions (page {page})…"));
         throttle();
-        let submissions: Vec<AtcoderSubmission> = client.get(&url).send()?.json()?;
+        fetch_json(client, &url)
+    })?;
+    if let Some((page, err)) = accepted.failed_page {
+        warn(format_args!(
+            "submissions page {} failed ({}); continuing with {} solved task(s) from earlier pages.",
+            page,
+            err,
+            accepted.tasks.len()
+        ));
+    }
+    Ok(accepted.tasks)
+}
+
+/// Accepted task ids, plus the page that cut the walk short under `allow_partial`.
+struct Accepted {
+    tasks: HashSet<String>,
+    failed_page: Option<(u32, Box<dyn Error>)>,
+}
+
+/// Walks the submission pages `fetch_page(page, from_second)` returns until one
+/// is empty or brings nothing newer. A failed first page is always an error; a
+/// later one ends the walk early when `allow_partial` is set.
+fn collect_accepted(
+    allow_partial: bool,
+    mut fetch_page: impl FnMut(u32, u64) -> Result<Vec<AtcoderSubmission>, Box<dyn Error>>,
+) -> Result<Accepted, Box<dyn Error>> {
+    let mut from_second: u64 = 0;
+    let mut accepted = Accepted {
+        tasks: HashSet::new(),
+        failed_page: None,
+    };
+    let mut page = 1;
+
+    loop {
+        let submissions = match fetch_page(page, from_second) {
+            Ok(submissions) => submissions,
+            Err(err) if allow_partial && page > 1 => {
+                accepted.failed_page = Some((page, err));
+                break;
+            }
+            Err(err) => return Err(err),
+        };
+        page += 1;
 
         if submissions.is_empty() {
             break;
@@ -129,7 +452,7 @@ fn fetch_user_submissions(client: &Client) -> Result<HashSet<String>, Box<dyn Er
         let mut max_epoch = from_second;
         for submission in submissions {
             if submission.result == "AC" {
-                accepted.insert(submission.problem_id.clone());
+                accepted.tasks.insert(submission.problem_id.clone());
             }
             if submission.epoch_second > max_epoch {
                 max_epoch = submission.epoch_second;
@@ -159,7 +482,56 @@ fn contest_number(contest_id: &str) -> u32 {
 
 fn problem_index(problem_id: &str) -> Option<String> {
     problem_id
-        .split('_')
-        .last()
+        .rsplit('_')
+        .next()
         .map(|idx| idx.to_ascii_lowercase())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn submission(problem_id: &str, result: &str, epoch_second: u64) -> AtcoderSubmission {
+        AtcoderSubmission {
+            problem_id: problem_id.to_string(),
+            result: result.to_string(),
+            epoch_second,
+        }
+    }
+
+    /// Serves page 1, then fails on page 2.
+    fn failing_second_page(
+        page: u32,
+        _from_second: u64,
+    ) -> Result<Vec<AtcoderSubmission>, Box<dyn Error>> {
+        match page {
+            1 => Ok(vec![
+                submission("abc300_a", "AC", 100),
+                submission("abc300_b", "WA", 200),
+            ]),
+            _ => Err("HTTP 503".into()),
+        }
+    }
+
+    #[test]
+    fn a_later_page_failure_keeps_earlier_pages_when_partial() {
+        let accepted = collect_accepted(true, failing_second_page).unwrap();
+        assert_eq!(accepted.tasks, HashSet::from(["abc300_a".to_string()]));
+        let (page, err) = accepted.failed_page.expect("the failure is reported");
+        assert_eq!(page, 2);
+        assert_eq!(err.to_string(), "HTTP 503");
+    }
+
+    #[test]
+    fn a_later_page_failure_is_an_error_otherwise() {
+        assert!(collect_accepted(false, failing_second_page).is_err());
+    }
+
+    #[test]
+    fn a_first_page_failure_is_always_an_error() {
+        for allow_partial in [false, true] {
+            let result = collect_accepted(allow_partial, |_, _| Err("HTTP 503".into()));
+            assert!(result.is_err());
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use crate::utils::cache_dir;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// How long a cached response is reused when `--max-age` is not given.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+static REFRESH: AtomicBool = AtomicBool::new(false);
+static MAX_AGE_SECS: AtomicU64 = AtomicU64::new(DEFAULT_TTL.as_secs());
+
+/// Ignores every cached entry for this run; takes precedence over `set_max_age`.
+pub fn set_refresh(refresh: bool) {
+    REFRESH.store(refresh, Ordering::Relaxed);
+}
+
+/// Overrides the staleness threshold for this run.
+pub fn set_max_age(max_age: Duration) {
+    MAX_AGE_SECS.store(max_age.as_secs(), Ordering::Relaxed);
+}
+
+/// Returns the cached body for `url` if it is younger than the max age.
+pub fn get(url: &str) -> Option<String> {
+    if REFRESH.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let path = entry_path(url)?;
+    let age = fs::metadata(&path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())?;
+    if age > Duration::from_secs(MAX_AGE_SECS.load(Ordering::Relaxed)) {
+        return None;
+    }
+    fs::read_to_string(path).ok()
+}
+
+/// Stores `body` for `url`. Failures are ignored; the cache is only an optimisation.
+pub fn put(url: &str, body: &str) {
+    if let Some(path) = entry_path(url) {
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, body);
+    }
+}
+
+fn entry_path(url: &str) -> Option<PathBuf> {
+    let name: String = url
+        .trim_start_matches("https://")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    cache_dir().map(|dir| dir.join(format!("{name}.json")))
+}
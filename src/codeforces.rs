@@ -1,30 +1,66 @@
+use crate::config::{self, Setting};
+use crate::error::{CfLvlError, EXIT_CONFIG, EXIT_FAILURE, EXIT_NOT_FOUND};
+use crate::history;
+use crate::skips;
+use crate::utils::{
+    ask_accept, ask_choice, display_path, fetch_json, fetch_json_cached, is_json, is_quiet,
+    is_verbose, now_epoch, open_url, print_json, render_format, report_timings, sanitize_filename,
+    terminal_width, warn, write_starter, ContestWindow, Rng, Spinner,
+};
 use reqwest::blocking::Client;
-use serde::Deserialize;
-use std::collections::{BTreeMap, HashSet};
-use std::env;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::error::Error;
-use std::fs::{self, OpenOptions};
-use std::io::Write;
+use std::fmt;
+use std::fs;
+use std::io::{self, IsTerminal, Write};
 use std::os::unix::process::CommandExt;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use std::process::Command;
-
-const CODEFORCES_HANDLE: &str = "Exonerate";
-const CODEFORCES_CPP_DIR: &str = "/Users/rogerchen/Developer/competitive/Codeforces";
-
-#[derive(Debug, Deserialize, Eq, PartialEq, Hash)]
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The lowest and highest ratings a Codeforces problem can have.
+pub const MIN_RATING: u32 = 800;
+pub const MAX_RATING: u32 = 3200;
+const AUTO_BAND_WIDTH: u32 = 200;
+const UNRATED_BAND: (u32, u32) = (MIN_RATING, 1000);
+const HISTORY_PLATFORM: &str = "codeforces";
+/// How many of the most recently started gym contests `--gym` draws from; each costs a request.
+const GYM_CONTESTS: usize = 10;
+/// Index letters a `virtual` set covers, one problem each.
+const VIRTUAL_INDICES: [char; 6] = ['A', 'B', 'C', 'D', 'E', 'F'];
+/// Index letters `dist --by-index` gives a column each.
+const DIST_INDICES: [char; 6] = ['A', 'B', 'C', 'D', 'E', 'F'];
+/// Most matches `search` lists; a narrower query shows the rest.
+const SEARCH_LIMIT: usize = 30;
+/// Gym contest ids start here, above every regular round.
+const GYM_FIRST_ID: u32 = 100_000;
+pub(crate) const LEVEL_STRATEGY_ENV: &str = "CF_LVL_LEVEL_STRATEGY";
+pub(crate) const INDEX_STRATEGY_ENV: &str = "CF_LVL_INDEX_STRATEGY";
+
+#[derive(Debug, Clone, Deserialize, Eq, PartialEq, Hash)]
 struct Problem {
     #[serde(rename = "contestId")]
     contest_id: u32,
     index: String,
     rating: u32,
     name: String,
+    tags: Vec<String>,
+    /// Accepted solutions from `problemStatistics`; 0 when unknown.
+    #[serde(default)]
+    solved_count: u32,
 }
 
 #[derive(Debug, Deserialize)]
 struct Contest {
     id: u32,
     name: String,
+    /// `BEFORE`, `CODING`, `PENDING_SYSTEM_TEST`, `SYSTEM_TEST` or `FINISHED`.
+    phase: String,
+    /// Missing for contests that have not been scheduled yet.
+    #[serde(rename = "startTimeSeconds")]
+    start_time_seconds: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -35,12 +71,55 @@ struct ApiResponse<T> {
 #[derive(Debug, Deserialize)]
 struct ProblemsetResult {
     problems: Vec<UnratedProblem>,
+    #[serde(rename = "problemStatistics", default)]
+    problem_statistics: Vec<ProblemStatistics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProblemStatistics {
+    #[serde(rename = "contestId")]
+    contest_id: u32,
+    index: String,
+    #[serde(rename = "solvedCount")]
+    solved_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct StandingsResult {
+    problems: Vec<UnratedProblem>,
 }
 
 #[derive(Debug, Deserialize)]
 struct Submission {
     problem: UnratedProblem,
     verdict: Option<String>,
+    author: Option<Party>,
+    #[serde(rename = "creationTimeSeconds")]
+    creation_time_seconds: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Party {
+    /// `CONTESTANT`, `PRACTICE`, `VIRTUAL`, `MANAGER` or `OUT_OF_COMPETITION`.
+    #[serde(rename = "participantType")]
+    participant_type: String,
+}
+
+impl Submission {
+    /// Whether this was sent during the round itself rather than in practice or virtually.
+    fn is_live(&self) -> bool {
+        self.author.as_ref().is_some_and(|author| {
+            matches!(
+                author.participant_type.as_str(),
+                "CONTESTANT" | "OUT_OF_COMPETITION"
+            )
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UserInfo {
+    rating: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -50,407 +129,3106 @@ struct UnratedProblem {
     index: String,
     name: String,
     rating: Option<u32>,
+    #[serde(default)]
+    tags: Vec<String>,
+    /// Filled in from the problemset's statistics, not the problem itself.
+    #[serde(skip)]
+    solved_count: u32,
 }
 
-pub fn run_level(client: &Client, level: u32) -> Result<(), Box<dyn Error>> {
-    if level < 8 || level > 32 {
-        println!("Error: Level must be an integer between 8 and 32 inclusive.");
-        return Ok(());
+impl Problem {
+    /// Interactive problems talk to a judge program, so they need an interactor to test locally.
+    fn is_interactive(&self) -> bool {
+        self.tags.iter().any(|tag| tag == "interactive")
     }
+}
 
-    let rated_problems = fetch_problem_set(client)?;
-    let div2_contests = fetch_contests(client)?;
-    let passed_problems = fetch_user_submissions(client)?;
+impl UnratedProblem {
+    /// Rating 0 stands for unrated.
+    fn into_problem(self) -> Problem {
+        Problem {
+            contest_id: self.contest_id,
+            index: self.index,
+            name: self.name,
+            rating: self.rating.unwrap_or(0),
+            tags: self.tags,
+            solved_count: self.solved_count,
+        }
+    }
+}
 
-    let solved: HashSet<(u32, String)> = passed_problems
-        .into_iter()
-        .map(|problem| (problem.contest_id, problem.index))
-        .collect();
+/// How to choose among the qualifying problems.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Strategy {
+    #[default]
+    Newest,
+    /// The oldest contest first, for working through the archive in order.
+    Oldest,
+    Random,
+    /// Prefer contests the history shows the fewest picks from.
+    Variety,
+    /// The most solved problem first: well tested, with a clear statement.
+    Popular,
+}
 
-    // Single-pass selection of the latest qualifying problem
-    let target_rating = level * 100;
-    let mut best: Option<Problem> = None;
-    for p in rated_problems.into_iter() {
-        if p.rating != target_rating {
-            continue;
-        }
-        if !div2_contests.contains(&p.contest_id) {
-            continue;
+impl Strategy {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "newest" => Ok(Strategy::Newest),
+            "oldest" => Ok(Strategy::Oldest),
+            "random" => Ok(Strategy::Random),
+            "variety" => Ok(Strategy::Variety),
+            "popular" => Ok(Strategy::Popular),
+            other => Err(format!(
+                "Unknown selection strategy '{other}'. Use 'newest', 'oldest', 'random', 'variety' or 'popular'."
+            )),
         }
-        if solved.contains(&(p.contest_id, p.index.clone())) {
-            continue;
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::Newest => "newest",
+            Strategy::Oldest => "oldest",
+            Strategy::Random => "random",
+            Strategy::Variety => "variety",
+            Strategy::Popular => "popular",
         }
+    }
 
-        match &best {
-            Some(cur) if cur.contest_id >= p.contest_id => {}
-            _ => best = Some(p),
+    /// Resolves the strategy for a mode: the CLI flag wins, then the mode's
+    /// configured strategy (environment, then config file), then newest.
+    fn resolve(
+        flag: Option<Strategy>,
+        configured: &Setting<String>,
+    ) -> Result<Self, Box<dyn Error>> {
+        if let Some(strategy) = flag {
+            return Ok(strategy);
         }
+        Strategy::from_name(&configured.value).map_err(|err| {
+            CfLvlError::Config {
+                message: format!("{}: {err}", configured.source.describe()),
+            }
+            .into()
+        })
     }
+}
 
-    if let Some(problem) = best {
-        let url = format!(
-            "https://codeforces.com/problemset/problem/{}/{}",
-            problem.contest_id, problem.index
-        );
+/// How to choose between candidates from the same contest (e.g. A1 and A2).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Tiebreak {
+    /// Lowest index string first, so `A1` before `A2` before `B`.
+    #[default]
+    Index,
+    /// Lowest rating first, then by index.
+    Rating,
+    Random,
+}
 
-        let file_info = match create_cpp_stub(&problem) {
-            Ok((path, created)) => Some((path, created)),
-            Err(err) => {
-                eprintln!("Warning: could not create starter file: {}", err);
-                None
+impl Tiebreak {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "index" => Ok(Tiebreak::Index),
+            "rating" => Ok(Tiebreak::Rating),
+            "random" => Ok(Tiebreak::Random),
+            other => Err(format!(
+                "Unknown tie-break '{other}'. Use 'index', 'rating' or 'random'."
+            )),
+        }
+    }
+
+    /// Picks one of `ties`, which must not be empty.
+    fn choose(self, mut ties: Vec<Problem>, rng: &mut Rng) -> Problem {
+        let i = match self {
+            Tiebreak::Index => (0..ties.len()).min_by(|&a, &b| ties[a].index.cmp(&ties[b].index)),
+            Tiebreak::Rating => (0..ties.len()).min_by(|&a, &b| {
+                ties[a]
+                    .rating
+                    .cmp(&ties[b].rating)
+                    .then_with(|| ties[a].index.cmp(&ties[b].index))
+            }),
+            Tiebreak::Random => {
+                // Sorted first so a seeded pick does not hang on problemset order
+                ties.sort_by(|a, b| a.index.cmp(&b.index));
+                Some(rng.below(ties.len()))
             }
         };
+        ties.swap_remove(i.unwrap_or(0))
+    }
+}
 
-        println!(
-            "Problem:   {} ({} {})",
-            problem.name, problem.contest_id, problem.index
-        );
-        println!("Rating:    {}", problem.rating);
-        if let Some((path, created)) = file_info {
-            let status = if created { "Created" } else { "Exists" };
-            println!("File:      {} ({})", get_display_path(&path), status);
+/// File name template for starter files, e.g. `{contest}{index}` or `{index}_{name}.cc`.
+/// `.cpp` is appended when the template has no extension of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameScheme(String);
 
-            if webbrowser::open(&url).is_err() {
-                println!("Warning: Failed to open problem in browser.");
-            }
+impl NameScheme {
+    const TOKENS: [&'static str; 4] = ["{index}", "{name}", "{contest}", "{rating}"];
 
-            // Get the path to open
-            let nvim_path = get_display_path(&path);
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut rest = template.to_string();
+        for token in Self::TOKENS {
+            rest = rest.replace(token, "");
+        }
+        if let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map_or(rest.len(), |i| start + i + 1);
+            return Err(format!(
+                "Unknown name scheme token '{}'. Use {}.",
+                &rest[start..end],
+                Self::TOKENS.join(", ")
+            ));
+        }
 
-            // Execute nvim, replacing the current process
-            let err = Command::new("nvim").arg(&nvim_path).exec();
+        let scheme = NameScheme(template.to_string());
+        // `{name}` alone can never be empty, so only literal-only templates can fail here
+        let blank = Problem {
+            contest_id: 1,
+            index: "A".to_string(),
+            rating: 800,
+            name: String::new(),
+            tags: Vec::new(),
+            solved_count: 0,
+        };
+        let file_name = scheme.file_name(&blank);
+        let stem = file_name
+            .rsplit_once('.')
+            .map_or(file_name.as_str(), |(stem, _)| stem);
+        if stem.trim().is_empty() || template.trim().is_empty() {
+            return Err(format!(
+                "Name scheme '{template}' produces an empty file name."
+            ));
+        }
+        Ok(scheme)
+    }
 
-            // If exec() returns, it means it failed to start nvim
-            eprintln!("Error: Failed to execute nvim: {}", err);
-            std::process::exit(1);
+    fn file_name(&self, problem: &Problem) -> String {
+        let rendered = self
+            .0
+            .replace("{index}", &problem.index)
+            .replace("{name}", &problem.name)
+            .replace("{contest}", &problem.contest_id.to_string())
+            .replace("{rating}", &problem.rating.to_string());
+        let name = sanitize_filename(&rendered);
+        let has_extension = self
+            .0
+            .rsplit('}')
+            .next()
+            .is_some_and(|literal| literal.contains('.'));
+        if has_extension {
+            name
         } else {
-            // If file creation failed, print browser warning here if not already printed
-            if webbrowser::open(&url).is_err() {
-                println!("Warning: Failed to open problem in browser.");
-            }
+            format!("{name}.cpp")
         }
-    } else {
-        println!(
-            "No problem with rating {} found (Level {}).",
-            target_rating, level
-        );
     }
-
-    Ok(())
 }
 
-pub fn run_distribution(client: &Client) -> Result<(), Box<dyn Error>> {
-    let rated_problems = fetch_problem_set(client)?;
-    let div2_contests = fetch_contests(client)?;
-
-    let mut distribution: BTreeMap<u32, u32> = BTreeMap::new();
+/// Contest families, classified from contest names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Division {
+    Div1,
+    Div2,
+    Div3,
+    Div4,
+    /// "Div. 1 + Div. 2" rounds.
+    Combined,
+    /// Educational rounds, rated for Div. 2.
+    Educational,
+    /// Global rounds, open to everyone.
+    Global,
+}
 
-    for problem in rated_problems.into_iter() {
-        if div2_contests.contains(&problem.contest_id) {
-            *distribution.entry(problem.rating).or_insert(0) += 1;
+impl Division {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "1" | "div1" => Ok(Division::Div1),
+            "2" | "div2" => Ok(Division::Div2),
+            "3" | "div3" => Ok(Division::Div3),
+            "4" | "div4" => Ok(Division::Div4),
+            "combined" | "1+2" => Ok(Division::Combined),
+            "edu" | "educational" => Ok(Division::Educational),
+            "global" => Ok(Division::Global),
+            other => Err(format!(
+                "Unknown division '{other}'. Use 1, 2, 3, 4, combined, edu or global."
+            )),
         }
     }
 
-    if distribution.is_empty() {
-        println!("No rated Codeforces Div. 2 problems found.");
-    } else {
-        println!("Rating distribution for Codeforces Div. 2 problems:");
-        let mut total: u32 = 0;
-        for (rating, count) in &distribution {
-            println!("  {}: {}", rating, count);
-            total += *count;
+    /// How the division reads in messages.
+    pub fn label(self) -> &'static str {
+        match self {
+            Division::Div1 => "Div. 1",
+            Division::Div2 => "Div. 2",
+            Division::Div3 => "Div. 3",
+            Division::Div4 => "Div. 4",
+            Division::Combined => "Div. 1 + Div. 2",
+            Division::Educational => "Educational",
+            Division::Global => "Global",
         }
-        println!("Total problems: {}", total);
     }
 
-    Ok(())
-}
-
-pub fn run_stats(client: &Client) -> Result<(), Box<dyn Error>> {
-    let div2_contests = fetch_contests(client)?;
-    let passed_problems = fetch_user_submissions(client)?;
-
-    let mut stats: BTreeMap<u32, u32> = BTreeMap::new();
+    /// Whether picking from `self` takes contests classified as `contest`;
+    /// Div. 2 also takes Educational rounds, which are rated for it.
+    fn covers(self, contest: Division) -> bool {
+        self == contest || (self == Division::Div2 && contest == Division::Educational)
+    }
 
-    for problem in passed_problems {
-        if div2_contests.contains(&problem.contest_id) {
-            *stats.entry(problem.rating).or_insert(0) += 1;
+    fn classify(contest_name: &str) -> Option<Self> {
+        if contest_name.starts_with("Educational") {
+            return Some(Division::Educational);
+        }
+        if contest_name.contains("Global Round") {
+            return Some(Division::Global);
+        }
+        let div1 = contest_name.contains("Div. 1");
+        let div2 = contest_name.contains("Div. 2");
+        match (div1, div2) {
+            (true, true) => Some(Division::Combined),
+            (true, false) => Some(Division::Div1),
+            (false, true) => Some(Division::Div2),
+            _ if contest_name.contains("Div. 3") => Some(Division::Div3),
+            _ if contest_name.contains("Div. 4") => Some(Division::Div4),
+            _ => None,
         }
     }
 
-    if stats.is_empty() {
-        println!("No solved Codeforces Div. 2 problems found.");
-    } else {
-        println!("Solved problems stats for Codeforces Div. 2:");
-        let mut total: u32 = 0;
-        let max_count = stats.values().max().unwrap_or(&0);
-        let scale = if *max_count > 50 {
-            50.0 / *max_count as f32
-        } else {
-            1.0
-        };
-
-        for (rating, count) in &stats {
-            let bar_len = (*count as f32 * scale).round() as usize;
-            let bar: String = std::iter::repeat('#').take(bar_len).collect();
-            println!("{:4}: {:3} | {}", rating, count, bar);
-            total += *count;
+    /// Divisions whose problems suit `rating` under `--tier`: Div. 3/4 below 1400,
+    /// Div. 2 from 1400 to 2099, and Div. 1, combined or global rounds from 2100.
+    fn for_rating(rating: u32) -> &'static [Division] {
+        match rating {
+            0..=1399 => &[Division::Div3, Division::Div4],
+            1400..=2099 => &[Division::Div2],
+            _ => &[Division::Div1, Division::Combined, Division::Global],
         }
-        println!("Total solved: {}", total);
     }
-
-    Ok(())
 }
 
-pub fn run_index(client: &Client, index_input: &str) -> Result<(), Box<dyn Error>> {
-    let letter = normalize_index(index_input)?;
-
-    let rated_problems = fetch_problem_set(client)?;
-    let div2_contests = fetch_contests(client)?;
-    let passed_problems = fetch_user_submissions(client)?;
-
-    let solved: HashSet<(u32, String)> = passed_problems
-        .into_iter()
-        .map(|problem| (problem.contest_id, problem.index))
-        .collect();
-
-    // Pick the latest Div. 2 problem matching the index letter and unsolved
-    let mut best: Option<Problem> = None;
-    for p in rated_problems.into_iter() {
-        if !div2_contests.contains(&p.contest_id) {
-            continue;
-        }
+/// Contest-name rule from `contests.include` / `contests.exclude`: `|`-separated
+/// alternatives, each a literal (case-sensitive) substring that `^` anchors to the
+/// start and `$` to the end of the name. An empty pattern matches nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContestPattern {
+    alternatives: Vec<String>,
+}
 
-        let mut chars = p.index.chars();
-        let starts_with_letter = chars
-            .next()
-            .map(|c| c.to_ascii_uppercase() == letter)
-            .unwrap_or(false);
-        if !starts_with_letter {
-            continue;
+impl ContestPattern {
+    pub fn parse(pattern: &str) -> Result<Self, String> {
+        if pattern.trim().is_empty() {
+            return Ok(ContestPattern::default());
         }
-
-        if solved.contains(&(p.contest_id, p.index.clone())) {
-            continue;
+        let alternatives: Vec<String> = pattern.split('|').map(str::to_string).collect();
+        if alternatives
+            .iter()
+            .any(|alt| alt.trim_start_matches('^').trim_end_matches('$').is_empty())
+        {
+            return Err(format!(
+                "Contest pattern '{pattern}' has an empty alternative."
+            ));
         }
+        Ok(ContestPattern { alternatives })
+    }
 
-        match &best {
-            Some(cur) if cur.contest_id >= p.contest_id => {}
-            _ => best = Some(p),
-        }
+    fn matches(&self, name: &str) -> bool {
+        self.alternatives.iter().any(|alt| {
+            let (start, alt) = alt
+                .strip_prefix('^')
+                .map_or((false, alt.as_str()), |a| (true, a));
+            let (end, alt) = alt.strip_suffix('$').map_or((false, alt), |a| (true, a));
+            match (start, end) {
+                (true, true) => name == alt,
+                (true, false) => name.starts_with(alt),
+                (false, true) => name.ends_with(alt),
+                (false, false) => name.contains(alt),
+            }
+        })
     }
+}
 
-    if let Some(problem) = best {
-        let url = format!(
-            "https://codeforces.com/problemset/problem/{}/{}",
-            problem.contest_id, problem.index
-        );
+/// Contest-id bounds from `--contests`, both ends included; `None` leaves a side open.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContestRange {
+    pub from: Option<u32>,
+    pub to: Option<u32>,
+}
 
-        let file_info = match create_cpp_stub(&problem) {
-            Ok((path, created)) => Some((path, created)),
-            Err(err) => {
-                eprintln!("Warning: could not create starter file: {}", err);
-                None
-            }
+impl ContestRange {
+    /// Parses `1700..`, `..1000` or `1500..1700`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let invalid =
+            || format!("Invalid contest range '{spec}'. Use e.g. 1700.., ..1000 or 1500..1700.");
+        let (from, to) = spec.trim().split_once("..").ok_or_else(invalid)?;
+        let bound = |value: &str| match value.trim() {
+            "" => Ok(None),
+            value => value.parse::<u32>().map(Some).map_err(|_| invalid()),
         };
+        let range = ContestRange {
+            from: bound(from)?,
+            to: bound(to)?,
+        };
+        match (range.from, range.to) {
+            (None, None) => Err(invalid()),
+            (Some(from), Some(to)) if from > to => Err(format!(
+                "Contest range '{spec}' is empty: {from} is above {to}."
+            )),
+            _ => Ok(range),
+        }
+    }
 
-        println!(
-            "Problem:   {} ({} {})",
-            problem.name, problem.contest_id, problem.index
-        );
-        println!("Rating:    {}", problem.rating);
-        if let Some((path, created)) = file_info {
-            let status = if created { "Created" } else { "Exists" };
-            println!("File:      {} ({})", get_display_path(&path), status);
+    fn contains(self, contest_id: u32) -> bool {
+        self.from.is_none_or(|from| contest_id >= from) && self.to.is_none_or(|to| contest_id <= to)
+    }
 
-            if webbrowser::open(&url).is_err() {
-                println!("Warning: Failed to open problem in browser.");
-            }
+    /// The range as given, e.g. `1700..`; `None` when unbounded.
+    pub fn describe(self) -> Option<String> {
+        let side = |bound: Option<u32>| bound.map_or(String::new(), |b| b.to_string());
+        (self.from.is_some() || self.to.is_some())
+            .then(|| format!("{}..{}", side(self.from), side(self.to)))
+    }
+}
 
-            // Get the path to open
-            let nvim_path = get_display_path(&path);
+/// Which contests count: `exclude` drops a contest outright, and `include`
+/// counts contests with no division in their name as Div. 2. April Fools and
+/// unrated rounds are dropped too unless `include_special` is set.
+#[derive(Debug, Default)]
+struct ContestRules {
+    include: ContestPattern,
+    exclude: ContestPattern,
+    include_special: bool,
+}
 
-            // Execute nvim, replacing the current process
-            let err = Command::new("nvim").arg(&nvim_path).exec();
+/// April Fools rounds and rounds marked unrated, whose problems make poor practice.
+fn is_special_round(contest_name: &str) -> bool {
+    let name = contest_name.to_ascii_lowercase();
+    name.contains("april fools") || name.contains("unrated")
+}
 
-            // If exec() returns, it means it failed to start nvim
-            eprintln!("Error: Failed to execute nvim: {}", err);
-            std::process::exit(1);
-        } else {
-            // If file creation failed, print browser warning here if not already printed
-            if webbrowser::open(&url).is_err() {
-                println!("Warning: Failed to open problem in browser.");
-            }
-        }
-    } else {
-        println!("No unsolved Codeforces Div. 2 '{}' problem found.", letter);
+impl ContestRules {
+    fn from_settings() -> Result<Self, Box<dyn Error>> {
+        let settings = config::settings()?;
+        let parse = |setting: &Setting<String>, key: &str| {
+            ContestPattern::parse(&setting.value)
+                .map_err(|err| format!("{key} ({}): {err}", setting.source.describe()))
+        };
+        Ok(ContestRules {
+            include: parse(&settings.contest_include, "contests.include")?,
+            exclude: parse(&settings.contest_exclude, "contests.exclude")?,
+            include_special: settings.include_special.value == "true",
+        })
     }
 
-    Ok(())
-}
-
-fn normalize_index(input: &str) -> Result<char, Box<dyn Error>> {
-    let trimmed = input.trim();
-    if trimmed.len() != 1 || !trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
-        return Err("Problem index must be a single letter (e.g., A, B, C).".into());
+    fn classify(&self, contest_name: &str) -> Option<Division> {
+        if self.exclude.matches(contest_name)
+            || (!self.include_special && is_special_round(contest_name))
+        {
+            return None;
+        }
+        Division::classify(contest_name)
+            .or_else(|| self.include.matches(contest_name).then_some(Division::Div2))
     }
-    Ok(trimmed.chars().next().unwrap().to_ascii_uppercase())
 }
 
-fn fetch_problem_set(client: &Client) -> Result<Vec<Problem>, Box<dyn Error>> {
-    let url = "https://codeforces.com/api/problemset.problems";
-    let response: ApiResponse<ProblemsetResult> = client.get(url).send()?.json()?;
-    let problems: Vec<UnratedProblem> = response.result.problems;
+/// Filters shared by the Codeforces pickers.
+#[derive(Debug, Default)]
+pub struct PickOptions {
+    /// Also skip problems with a rejected submission, not just accepted ones.
+    pub exclude_attempted: bool,
+    /// Pick among solved problems instead, for review.
+    pub review: bool,
+    /// Pick among problems with a rejected submission and no accepted one.
+    pub retry: bool,
+    /// Selection strategy from the command line, overriding the per-mode default.
+    pub strategy: Option<Strategy>,
+    /// Verdicts that count as solved; empty means `OK` only.
+    pub solved_verdicts: Vec<String>,
+    /// Only draw from this division; overrides `tier`. Div. 2 when neither is set.
+    pub division: Option<Division>,
+    /// Choose the division from each problem's rating (see `Division::for_rating`).
+    pub tier: bool,
+    /// Print every candidate instead of picking one.
+    pub listing: Option<Listing>,
+    /// Also draw from contests that have not finished yet.
+    pub include_unfinished: bool,
+    /// Chooses between candidates the strategy ranks equally.
+    pub tiebreak: Tiebreak,
+    /// Report how many candidates are left instead of picking one.
+    pub remaining: bool,
+    /// Template from `--format` replacing the default pick summary.
+    pub format: Option<String>,
+    /// How starter files are named; the configured scheme when unset.
+    pub name_scheme: Option<NameScheme>,
+    /// Print only the starter file's absolute path on stdout, without opening anything.
+    pub print_path: bool,
+    /// Show the pick without creating a file, recording history or opening anything.
+    pub dry_run: bool,
+    /// Skip the starter file (and so the editor) but still record and open the pick.
+    pub no_file: bool,
+    /// Only problems whose index starts with this letter, on top of the mode's own rule.
+    pub index: Option<char>,
+    /// Only problems carrying every one of these tags (lowercase).
+    pub tags: Vec<String>,
+    /// Accept problems carrying any of `tags` rather than all of them.
+    pub any_tag: bool,
+    /// Never pick problems carrying any of these tags; `codeforces.exclude_tags` when unset.
+    pub exclude_tags: Option<Vec<String>>,
+    /// Propose candidates one at a time and wait for y/n before opening one.
+    pub confirm: bool,
+    /// Only contests with ids in this range.
+    pub contests: ContestRange,
+    /// Only contests that started within this window (`--since` / `--before`).
+    pub window: ContestWindow,
+    /// Draw from the newest gym contests instead of the problemset; their problems are unrated.
+    pub gym: bool,
+    /// Seed for `--random` and `--tiebreak random`, so a pick can be repeated.
+    pub seed: Option<u64>,
+    /// Skip problems with any submission in this many past days.
+    pub skip_recent_days: Option<u64>,
+    /// Only problems at least this many people have solved.
+    pub min_solved: Option<u32>,
+    /// How far from the level a pick may fall back; `codeforces.fallback` when unset.
+    pub fallback: Option<u32>,
+    /// Leave the tags out of the pick output, since they hint at the solution.
+    pub spoiler_free: bool,
+    /// Keep Div. 2 picks to Div. 2 rounds, without the A-D of combined ones.
+    pub exclude_combined: bool,
+    /// Also skip problems this other handle has solved.
+    pub also_unsolved_by: Option<String>,
+    /// Leave interactive problems out.
+    pub exclude_interactive: bool,
+    /// Only problems this other handle has solved.
+    pub solved_by: Option<String>,
+    /// List at most this many candidates.
+    pub limit: Option<usize>,
+    /// Pick this many distinct problems in one run (`--count`).
+    pub count: Option<usize>,
+}
 
-    Ok(problems
-        .into_iter()
-        .filter_map(|problem| {
-            problem.rating.map(|rating| Problem {
-                contest_id: problem.contest_id,
-                index: problem.index,
-                name: problem.name,
-                rating,
-            })
-        })
-        .collect())
+/// Output format for `--list`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Listing {
+    Text,
+    /// Aligned columns under a header row, for `cf-lvl list`.
+    Table,
+    /// One JSON array.
+    Json,
+    /// One JSON object per line, flushed as it is written.
+    JsonLines,
 }
 
-fn fetch_contests(client: &Client) -> Result<HashSet<u32>, Box<dyn Error>> {
-    let url = "https://codeforces.com/api/contest.list";
-    let response: ApiResponse<Vec<Contest>> = client.get(url).send()?.json()?;
+/// Result of a pick request.
+enum Outcome {
+    Picked(Problem),
+    /// Candidates were printed by `--list`, counted by `--remaining` or opened
+    /// as a `--count` batch, or `--confirm` was stopped; nothing more to report.
+    Listed,
+    NotFound,
+}
 
-    Ok(response
-        .result
-        .into_iter()
-        .filter(|contest| contest.name.contains("Div. 2") && !contest.name.contains("Div. 1"))
-        .map(|contest| contest.id)
-        .collect())
+/// A candidate as written by `--list --json`/`--jsonl`.
+#[derive(Debug, Serialize)]
+struct ProblemRecord<'a> {
+    contest_id: u32,
+    index: &'a str,
+    name: &'a str,
+    rating: u32,
+    url: String,
+    interactive: bool,
 }
 
-fn fetch_user_submissions(client: &Client) -> Result<HashSet<Problem>, Box<dyn Error>> {
-    let url = format!(
-        "https://codeforces.com/api/user.status?handle={}",
-        CODEFORCES_HANDLE
-    );
-    let response: ApiResponse<Vec<Submission>> = client.get(&url).send()?.json()?;
+/// A pick as written under `--json`.
+#[derive(Debug, Serialize)]
+struct PickRecord<'a> {
+    platform: &'static str,
+    /// Contest id plus index, e.g. `1900A`.
+    id: String,
+    #[serde(flatten)]
+    problem: ProblemRecord<'a>,
+    /// Absolute path of the starter file, if there is one.
+    path: Option<String>,
+    created: Option<bool>,
+}
 
-    Ok(response
-        .result
-        .into_iter()
-        .filter_map(|submission| {
-            if submission.verdict.as_deref() == Some("OK") {
-                submission.problem.rating.map(|rating| Problem {
-                    contest_id: submission.problem.contest_id,
-                    index: submission.problem.index.clone(),
-                    name: submission.problem.name.clone(),
-                    rating,
-                })
-            } else {
-                None
-            }
-        })
-        .collect())
+/// A problem plus your status, as `search` and `latest` write it under `--json`.
+#[derive(Debug, Serialize)]
+struct StatusRecord<'a> {
+    #[serde(flatten)]
+    problem: ProblemRecord<'a>,
+    solved: bool,
 }
 
-fn create_cpp_stub(problem: &Problem) -> Result<(PathBuf, bool), Box<dyn Error>> {
-    fs::create_dir_all(CODEFORCES_CPP_DIR)?;
+/// One rating's row in `dist` and `stats` under `--json`.
+#[derive(Debug, Serialize)]
+struct RatingCount {
+    rating: u32,
+    count: u32,
+    /// How many of `count` you have solved, under `dist --progress`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    solved: Option<u32>,
+}
 
-    let file_name = format!("{}.cpp", sanitize_filename(&problem.name));
-    let path = PathBuf::from(CODEFORCES_CPP_DIR).join(file_name);
+impl<'a> PickRecord<'a> {
+    fn new(problem: &'a Problem, file_info: Option<&(PathBuf, bool)>) -> Self {
+        PickRecord {
+            platform: HISTORY_PLATFORM,
+            id: format!("{}{}", problem.contest_id, problem.index),
+            problem: ProblemRecord::from(problem),
+            path: file_info.map(|(path, _)| {
+                fs::canonicalize(path)
+                    .unwrap_or_else(|_| path.clone())
+                    .display()
+                    .to_string()
+            }),
+            created: file_info.map(|(_, created)| *created),
+        }
+    }
+}
 
-    if path.exists() {
-        return Ok((path, false));
+impl<'a> From<&'a Problem> for ProblemRecord<'a> {
+    fn from(problem: &'a Problem) -> Self {
+        ProblemRecord {
+            contest_id: problem.contest_id,
+            index: &problem.index,
+            name: &problem.name,
+            rating: problem.rating,
+            url: problem_url(problem),
+            interactive: problem.is_interactive(),
+        }
     }
+}
 
-    let mut file = OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(&path)?;
+impl PickOptions {
+    fn accepts_division(&self, division: Division, rating: u32) -> bool {
+        match self.division {
+            Some(fixed) => fixed.covers(division),
+            None if self.tier => Division::for_rating(rating)
+                .iter()
+                .any(|tier| tier.covers(division)),
+            None => Division::Div2.covers(division),
+        }
+    }
 
-    let starter = r#"#include <iostream>
+    /// Whether `problem` comes from a contest in the selected division(s).
+    fn accepts_problem(&self, divisions: &HashMap<u32, Division>, problem: &Problem) -> bool {
+        let Some(&division) = divisions.get(&problem.contest_id) else {
+            return false;
+        };
+        if self.accepts_division(division, problem.rating) {
+            return true;
+        }
+        // A combined round's A-D are ordinary Div. 2 problems
+        division == Division::Combined
+            && problem.index.as_str() < "E"
+            && self.accepts_division(Division::Div2, problem.rating)
+            && self.takes_combined()
+    }
 
-void solve();
+    /// Whether Div. 2 picks also take the A-D of combined rounds: not under
+    /// `exclude_combined`, else as `contests.include_combined` says.
+    fn takes_combined(&self) -> bool {
+        !self.exclude_combined
+            && config::settings()
+                .map_or(true, |settings| settings.include_combined.value != "false")
+    }
 
-int main() {
-    int count_test_cases;
+    /// Like `accepts_division` for a whole contest, where `tier` has no rating
+    /// to go by and so takes every division.
+    fn accepts_contest(&self, division: Division) -> bool {
+        match self.division {
+            Some(fixed) => fixed.covers(division),
+            None => self.tier || Division::Div2.covers(division),
+        }
+    }
 
-    std::cin >> count_test_cases;
+    /// The selected division(s) as they read in messages.
+    fn division_label(&self) -> &'static str {
+        if self.gym {
+            return "gym";
+        }
+        match self.division {
+            Some(fixed) => fixed.label(),
+            None if self.tier => "tiered",
+            None => Division::Div2.label(),
+        }
+    }
 
-    while (count_test_cases--) {
-        solve();
+    /// Tags the problemset request itself can filter by: all of `tags`, so none
+    /// under `any_tag`.
+    fn server_tags(&self) -> &[String] {
+        if self.any_tag {
+            &[]
+        } else {
+            &self.tags
+        }
     }
-}
 
-void solve() {
-}
-"#;
+    /// The generator behind random choices: `seed` when set, else the clock.
+    fn rng(&self) -> Rng {
+        self.seed.map_or_else(Rng::from_time, Rng::seeded)
+    }
 
-    file.write_all(starter.as_bytes())?;
-    Ok((path, true))
-}
+    /// The fallback width from `fallback` or, failing that, the config.
+    fn fallback_width(&self) -> Result<u32, Box<dyn Error>> {
+        if let Some(width) = self.fallback {
+            return Ok(width);
+        }
+        Ok(
+            parse_fallback(&config::settings()?.fallback.value).map_err(|err| {
+                CfLvlError::Config {
+                    message: format!("codeforces.fallback: {err}"),
+                }
+            })?,
+        )
+    }
 
-fn sanitize_filename(name: &str) -> String {
-    let cleaned: String = name
-        .chars()
-        .map(|c| {
-            if c == '/' || c == '\\' {
-                '-'
-            } else if c.is_control() {
-                ' '
-            } else {
-                c
-            }
-        })
-        .collect();
+    /// The tags `exclude_tags` or, failing that, the config rules out.
+    fn excluded_tags(&self) -> Result<Vec<String>, Box<dyn Error>> {
+        if let Some(tags) = &self.exclude_tags {
+            return Ok(tags.clone());
+        }
+        let configured = &config::settings()?.exclude_tags.value;
+        if configured.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        Ok(parse_tags(configured).map_err(|err| CfLvlError::Config {
+            message: format!("codeforces.exclude_tags: {err}"),
+        })?)
+    }
 
-    let trimmed = cleaned.trim();
-    if trimmed.is_empty() {
-        "problem".to_string()
-    } else {
-        trimmed.to_string()
+    /// Applies `index`, `tags` and the `excluded` tags, which combine with any mode.
+    fn passes_filters(&self, problem: &Problem, excluded: &[String]) -> bool {
+        let index_ok = self.index.is_none_or(|letter| {
+            problem
+                .index
+                .chars()
+                .next()
+                .is_some_and(|c| c.to_ascii_uppercase() == letter)
+        });
+        let has = |tag: &String| problem.tags.iter().any(|t| t.eq_ignore_ascii_case(tag));
+        let tags_ok = if self.tags.is_empty() {
+            true
+        } else if self.any_tag {
+            self.tags.iter().any(has)
+        } else {
+            self.tags.iter().all(has)
+        };
+        let popular_enough = self
+            .min_solved
+            .is_none_or(|min| problem.solved_count >= min);
+        index_ok
+            && tags_ok
+            && popular_enough
+            && !(self.exclude_interactive && problem.is_interactive())
+            && !excluded.iter().any(has)
     }
-}
 
-fn get_display_path(p: &Path) -> String {
-    // 1. Try relative path from CWD
-    if let Ok(cwd) = env::current_dir() {
-        if let Some(diff) = diff_paths(p, &cwd) {
-            return diff.display().to_string();
+    /// Describes `index`, `tags`, `contests` and `window` for status lines, e.g. `, index D, tags graphs+dp`
+    /// (`graphs|dp` under `any_tag`).
+    fn filter_note(&self) -> String {
+        let mut note = String::new();
+        if let Some(letter) = self.index {
+            note.push_str(&format!(", index {letter}"));
+        }
+        if !self.tags.is_empty() {
+            let joiner = if self.any_tag { "|" } else { "+" };
+            note.push_str(&format!(", tags {}", self.tags.join(joiner)));
+        }
+        if let Some(range) = self.contests.describe() {
+            note.push_str(&format!(", contests {range}"));
+        }
+        if let Some(window) = self.window.describe() {
+            note.push_str(&format!(", {window}"));
         }
+        if let Some(min) = self.min_solved {
+            note.push_str(&format!(", solved by {min}+"));
+        }
+        if self.exclude_interactive {
+            note.push_str(", not interactive");
+        }
+        if let Some(rival) = &self.also_unsolved_by {
+            note.push_str(&format!(", unsolved by {rival}"));
+        }
+        if let Some(rival) = &self.solved_by {
+            note.push_str(&format!(", solved by {rival}"));
+        }
+        note
     }
 
-    // 2. Try ~ replacement
-    if let Ok(home) = env::var("HOME") {
-        let home_path = Path::new(&home);
-        if let Ok(stripped) = p.strip_prefix(home_path) {
-            return format!("~/{}", stripped.display());
+    /// The pool named in messages: solved, attempted or unsolved.
+    pub fn pool_label(&self) -> &'static str {
+        if self.review {
+            "solved"
+        } else if self.retry {
+            "attempted"
+        } else {
+            "unsolved"
         }
     }
 
-    // 3. Fallback to absolute
-    p.display().to_string()
-}
-
-fn diff_paths(path: &Path, base: &Path) -> Option<PathBuf> {
-    let path_comps: Vec<_> = path.components().collect();
-    let base_comps: Vec<_> = base.components().collect();
-
-    let mut i = 0;
-    while i < path_comps.len() && i < base_comps.len() && path_comps[i] == base_comps[i] {
-        i += 1;
+    fn counts_as_solved(&self, verdict: Option<&str>) -> bool {
+        match verdict {
+            Some(verdict) if self.solved_verdicts.is_empty() => verdict == "OK",
+            Some(verdict) => self.solved_verdicts.iter().any(|v| v == verdict),
+            None => false,
+        }
     }
+}
 
-    if i == 0 {
-        return None;
+/// Verdicts the Codeforces API can report for a submission.
+const KNOWN_VERDICTS: &[&str] = &[
+    "FAILED",
+    "OK",
+    "PARTIAL",
+    "COMPILATION_ERROR",
+    "RUNTIME_ERROR",
+    "WRONG_ANSWER",
+    "PRESENTATION_ERROR",
+    "TIME_LIMIT_EXCEEDED",
+    "MEMORY_LIMIT_EXCEEDED",
+    "IDLENESS_LIMIT_EXCEEDED",
+    "SECURITY_VIOLATED",
+    "CRASHED",
+    "INPUT_PREPARATION_CRASHED",
+    "CHALLENGED",
+    "SKIPPED",
+    "TESTING",
+    "REJECTED",
+];
+
+/// Parses a comma-separated tag list such as `graphs,binary search`.
+pub fn parse_tags(spec: &str) -> Result<Vec<String>, String> {
+    let tags: Vec<String> = spec
+        .split(',')
+        .map(|tag| tag.trim().to_ascii_lowercase())
+        .collect();
+    if tags.iter().any(|tag| tag.is_empty()) {
+        return Err(format!(
+            "Invalid tag list '{spec}': tags must not be empty."
+        ));
     }
+    Ok(tags)
+}
 
-    let mut new_path = PathBuf::new();
-    for _ in i..base_comps.len() {
-        new_path.push("..");
+/// Parses a comma-separated verdict list such as `OK,PARTIAL`.
+pub fn parse_verdicts(spec: &str) -> Result<Vec<String>, String> {
+    let verdicts: Vec<String> = spec
+        .split(',')
+        .map(|v| v.trim().to_ascii_uppercase())
+        .filter(|v| !v.is_empty())
+        .collect();
+    if verdicts.is_empty() {
+        return Err("Provide at least one verdict, e.g. --solved-verdicts OK,PARTIAL.".to_string());
     }
-    for component in &path_comps[i..] {
-        new_path.push(component);
+    if let Some(unknown) = verdicts
+        .iter()
+        .find(|v| !KNOWN_VERDICTS.contains(&v.as_str()))
+    {
+        return Err(format!(
+            "Unknown verdict '{}'. Known verdicts: {}.",
+            unknown,
+            KNOWN_VERDICTS.join(", ")
+        ));
     }
+    Ok(verdicts)
+}
 
-    Some(new_path)
+/// Problems the user has submitted to, split by outcome.
+#[derive(Default)]
+struct UserSubmissions {
+    /// `(contest_id, index)` of every accepted problem, rated or not.
+    solved: HashSet<(u32, String)>,
+    /// The rated subset of `solved`, for rating statistics.
+    solved_rated: HashSet<Problem>,
+    /// Every problem with at least one submission, whatever the verdict.
+    attempted: HashSet<(u32, String)>,
+    /// Contests with a submission sent during the round.
+    participated: HashSet<u32>,
+    /// Problems with any submission inside the `skip_recent_days` window.
+    recent: HashSet<(u32, String)>,
+}
+
+impl UserSubmissions {
+    /// Returns the `(contest_id, index)` keys the pickers should skip.
+    fn excluded(self, options: &PickOptions) -> HashSet<(u32, String)> {
+        let mut excluded = self.solved;
+        if options.exclude_attempted {
+            excluded.extend(self.attempted);
+        }
+        excluded.extend(self.recent);
+        excluded
+    }
+}
+
+pub fn run_level(client: &Client, level: u32, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    if !(8..=32).contains(&level) {
+        return Err("Level must be an integer between 8 and 32 inclusive.".into());
+    }
+
+    let target_rating = level * 100;
+    let strategy = Strategy::resolve(options.strategy, &config::settings()?.level_strategy)?;
+    let fallback = options.fallback_width()?;
+    // --list and --remaining report the level itself; only a pick falls back
+    let fallbacks = if options.remaining || options.listing.is_some() {
+        Vec::new()
+    } else {
+        fallback_ratings(target_rating, fallback)
+    };
+    // One pool covers every fallback rating, so the account is fetched only once
+    let pool = collect_candidates(client, options, |p| {
+        p.rating == target_rating || fallbacks.contains(&p.rating)
+    })?;
+    let rating = std::iter::once(target_rating)
+        .chain(fallbacks.iter().copied())
+        .find(|&rating| pool.has_rating(rating))
+        .unwrap_or(target_rating);
+    if rating != target_rating {
+        say(
+            options,
+            format_args!(
+                "No {}problem left at rating {}; falling back to {}.",
+                review_prefix(options),
+                target_rating,
+                rating
+            ),
+        );
+    }
+    let criterion = format!("rating {rating}");
+    let outcome = pick_from(pool.at_rating(rating), options, strategy, &criterion)?;
+    match outcome {
+        Outcome::Picked(problem) => open_problem(&problem, options),
+        Outcome::Listed => {}
+        Outcome::NotFound => not_found(format_args!(
+            "No {}problem with rating {} found (Level {}{}{}).",
+            review_prefix(options),
+            target_rating,
+            level,
+            if fallback > 0 {
+                format!(", nor within {fallback}")
+            } else {
+                String::new()
+            },
+            options.filter_note()
+        )),
+    }
+
+    Ok(())
+}
+
+/// Ratings to try after `target`, nearest first and the easier one of each pair
+/// first, up to `width` away.
+fn fallback_ratings(target: u32, width: u32) -> Vec<u32> {
+    let mut ratings = Vec::new();
+    for distance in (100..=width).step_by(100) {
+        if target >= MIN_RATING + distance {
+            ratings.push(target - distance);
+        }
+        if target + distance <= MAX_RATING {
+            ratings.push(target + distance);
+        }
+    }
+    ratings
+}
+
+/// Reads a `codeforces.fallback` value: how far from the level a pick may stray.
+pub fn parse_fallback(value: &str) -> Result<u32, String> {
+    match value.trim() {
+        "0" => Ok(0),
+        "100" => Ok(100),
+        "200" => Ok(200),
+        other => Err(format!("Fallback '{other}' must be 0, 100 or 200.")),
+    }
+}
+
+/// Reads a `codeforces.level_offset` value such as `+200` or `-100` as levels.
+pub fn parse_level_offset(value: &str) -> Result<i32, String> {
+    let trimmed = value.trim();
+    match trimmed.strip_prefix('+').unwrap_or(trimmed).parse::<i32>() {
+        Ok(points) if points % 100 == 0 && (-2400..=2400).contains(&points) => Ok(points / 100),
+        _ => Err(format!(
+            "'{value}' is not a rating offset; use a multiple of 100 such as +200 or -100."
+        )),
+    }
+}
+
+/// Picks at the user's rating rounded to the nearest 100, moved by `offset` levels.
+pub fn run_relative(
+    client: &Client,
+    offset: i32,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    run_offset(client, offset, false, options)
+}
+
+/// Picks at the rounded rating plus `codeforces.level_offset`, clamped to 8-32.
+pub fn run_from_rating(client: &Client, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let offset = parse_level_offset(&config::settings()?.level_offset.value).map_err(|err| {
+        CfLvlError::Config {
+            message: format!("codeforces.level_offset: {err}"),
+        }
+    })?;
+    run_offset(client, offset, true, options)
+}
+
+fn run_offset(
+    client: &Client,
+    offset: i32,
+    clamp: bool,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let Some(rating) = fetch_user_rating(client)? else {
+        return Err(if clamp {
+            "Picking from your rating needs a Codeforces rating; this handle is unrated. \
+             Give a level instead, e.g. cf-lvl 8."
+        } else {
+            "Relative levels need a Codeforces rating; this handle is unrated."
+        }
+        .into());
+    };
+    let base = (rating + 50) / 100;
+    let mut level = i64::from(base) + i64::from(offset);
+    if clamp {
+        level = level.clamp(8, 32);
+    } else if !(8..=32).contains(&level) {
+        return Err(format!(
+            "Level {level} (rating {rating} rounded, {offset:+}) is outside 8-32."
+        )
+        .into());
+    }
+    say(
+        options,
+        format_args!(
+            "Detected rating: {} (level {}), target level {}",
+            rating, base, level
+        ),
+    );
+    run_level(client, level as u32, options)
+}
+
+pub fn run_ratings(
+    client: &Client,
+    ratings: &BTreeSet<u32>,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let strategy = Strategy::resolve(options.strategy, &config::settings()?.level_strategy)?;
+    let list: Vec<String> = ratings.iter().map(|r| r.to_string()).collect();
+    let criterion = format!("ratings {}", list.join(", "));
+    match pick(client, options, strategy, &criterion, |p| {
+        ratings.contains(&p.rating)
+    })? {
+        Outcome::Picked(problem) => open_problem(&problem, options),
+        Outcome::Listed => {}
+        Outcome::NotFound => {
+            not_found(format_args!(
+                "No {}problem with ratings {} found.",
+                review_prefix(options),
+                list.join(", ")
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Picks from a rating band starting at the user's current rating, rounded down to
+/// the nearest 100, and extending `AUTO_BAND_WIDTH` above it.
+pub fn run_auto(client: &Client, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let (low, high) = match fetch_user_rating(client)? {
+        Some(rating) => {
+            let low = (rating / 100 * 100).clamp(MIN_RATING, MAX_RATING);
+            say(options, format_args!("Detected rating: {}", rating));
+            (low, (low + AUTO_BAND_WIDTH).min(MAX_RATING))
+        }
+        None => {
+            say(
+                options,
+                format_args!("Detected rating: unrated (using the default band)"),
+            );
+            UNRATED_BAND
+        }
+    };
+    say(options, format_args!("Target band:     {}-{}", low, high));
+
+    let strategy = Strategy::resolve(options.strategy, &config::settings()?.level_strategy)?;
+    let criterion = format!("ratings {}-{}", low, high);
+    match pick(client, options, strategy, &criterion, |p| {
+        (low..=high).contains(&p.rating)
+    })? {
+        Outcome::Picked(problem) => open_problem(&problem, options),
+        Outcome::Listed => {}
+        Outcome::NotFound => not_found(format_args!(
+            "No {}problem with rating {}-{} found.",
+            review_prefix(options),
+            low,
+            high
+        )),
+    }
+
+    Ok(())
+}
+
+/// Walks ratings upward from 800 and picks from the lowest one that still has a candidate.
+pub fn run_first_unsolved(client: &Client, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let pool = collect_candidates(client, options, |_| true)?;
+    let Some(rating) = pool.candidates.iter().map(|p| p.rating).min() else {
+        not_found(format_args!(
+            "No {}Codeforces {} problem found at any rating.",
+            review_prefix(options),
+            options.division_label()
+        ));
+    };
+
+    let strategy = Strategy::resolve(options.strategy, &config::settings()?.level_strategy)?;
+    let below = rating.saturating_sub(MIN_RATING) / 100;
+    say(
+        options,
+        format_args!(
+            "Lowest rating with a candidate: {} ({} lower rating(s) exhausted)",
+            rating, below
+        ),
+    );
+    let criterion = format!("rating {rating}");
+    if let Outcome::Picked(problem) =
+        pick_from(pool.at_rating(rating), options, strategy, &criterion)?
+    {
+        open_problem(&problem, options);
+    }
+
+    Ok(())
+}
+
+/// Checks `handle` against `user.info`; Codeforces answers unknown handles with HTTP 400.
+pub fn user_exists(client: &Client, handle: &str) -> Result<bool, Box<dyn Error>> {
+    let url = format!("https://codeforces.com/api/user.info?handles={handle}");
+    let _spinner = Spinner::start("Checking handle…");
+    match fetch_json::<ApiResponse<Vec<UserInfo>>>(client, &url) {
+        Ok(response) => Ok(!response.result.is_empty()),
+        Err(err) => match err.downcast_ref::<CfLvlError>() {
+            Some(CfLvlError::Http { status: 400, .. }) => Ok(false),
+            _ => Err(err),
+        },
+    }
+}
+
+/// Opens `handle`'s profile, or their submissions list, in the browser.
+pub fn run_me(handle: &str, submissions: bool) -> Result<(), Box<dyn Error>> {
+    let url = if submissions {
+        format!("https://codeforces.com/submissions/{handle}")
+    } else {
+        format!("https://codeforces.com/profile/{handle}")
+    };
+    if is_json() {
+        return print_json(&serde_json::json!({ "url": url }));
+    }
+    if open_url(&url) {
+        println!("Opening {url}");
+    } else {
+        println!("{url}");
+    }
+    Ok(())
+}
+
+/// Opens a specific problem, bypassing selection.
+pub fn run_problem(
+    client: &Client,
+    contest_id: u32,
+    index: &str,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let index = index.to_ascii_uppercase();
+    // Unrated problems count here: the user asked for this one by name
+    let problem = fetch_all_problems(client, &[])?
+        .into_iter()
+        .find(|p| p.contest_id == contest_id && p.index == index)
+        .map(UnratedProblem::into_problem)
+        .ok_or_else(|| CfLvlError::NotFound {
+            message: format!("Problem {contest_id}{index} not found in the Codeforces problemset."),
+        })?;
+    open_problem(&problem, options);
+    Ok(())
+}
+
+/// Opens the first unsolved problem, by index, of the newest contest you took part
+/// in live that still has one: upsolving your last round.
+pub fn run_upsolve(client: &Client, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let submissions = fetch_user_submissions(client, options)?;
+    if submissions.participated.is_empty() {
+        not_found(format_args!(
+            "No contests found that you took part in during the round."
+        ));
+    }
+
+    let skipped = read_skips();
+    let mut by_contest: BTreeMap<u32, Vec<Problem>> = BTreeMap::new();
+    for problem in fetch_all_problems(client, &[])? {
+        if submissions.participated.contains(&problem.contest_id)
+            && !submissions
+                .solved
+                .contains(&(problem.contest_id, problem.index.clone()))
+            && !skipped.contains(&format!("{}{}", problem.contest_id, problem.index))
+        {
+            by_contest
+                .entry(problem.contest_id)
+                .or_default()
+                .push(problem.into_problem());
+        }
+    }
+    // Newest contest first; A before B, and C1 before C2, within it
+    let Some((contest_id, mut left)) = by_contest.into_iter().next_back() else {
+        not_found(format_args!(
+            "Nothing left to upsolve: every problem of the {} contest(s) you took part in is solved.",
+            submissions.participated.len()
+        ));
+    };
+    left.sort_by(|a, b| a.index.cmp(&b.index));
+    say(
+        options,
+        format_args!(
+            "Upsolving contest {}: {} problem(s) left.",
+            contest_id,
+            left.len()
+        ),
+    );
+    open_problem(&left.swap_remove(0), options);
+    Ok(())
+}
+
+/// Assembles a practice set of one A through F from contests you have never sent
+/// anything to, creates their starter files, then runs `timer` if given.
+pub fn run_virtual(
+    client: &Client,
+    timer: Option<Duration>,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let pool = collect_candidates(client, options, |_| true)?;
+    let fresh: Vec<Problem> = pool
+        .candidates
+        .into_iter()
+        .filter(|p| !pool.touched.contains(&p.contest_id))
+        .collect();
+
+    let strategy = Strategy::resolve(options.strategy, &config::settings()?.index_strategy)?;
+    let mut rng = options.rng();
+    let mut set = Vec::new();
+    for letter in VIRTUAL_INDICES {
+        let with_letter: Vec<Problem> = fresh
+            .iter()
+            .filter(|p| p.index.starts_with(letter))
+            .cloned()
+            .collect();
+        match select(with_letter, strategy, options, &mut rng) {
+            Some(problem) => set.push(problem),
+            None => warn(format_args!(
+                "no problem {letter} left in contests you haven't touched"
+            )),
+        }
+    }
+    if set.is_empty() {
+        not_found(format_args!(
+            "No untouched Codeforces {} contest has problems left{}.",
+            options.division_label(),
+            options.filter_note()
+        ));
+    }
+
+    say(
+        options,
+        format_args!("Virtual set: {} problem(s), A to F.", set.len()),
+    );
+    open_batch(&set, options)?;
+    if let Some(duration) = timer.filter(|_| !options.dry_run) {
+        run_timer(duration)?;
+    }
+    Ok(())
+}
+
+/// Counts `duration` down on stderr once a minute, then rings the terminal bell.
+fn run_timer(duration: Duration) -> Result<(), Box<dyn Error>> {
+    let end = Instant::now() + duration;
+    loop {
+        let left = end.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            break;
+        }
+        let minutes = left.as_secs().div_ceil(60);
+        eprint!("\rTime left: {}h{:02}m ", minutes / 60, minutes % 60);
+        io::stderr().flush()?;
+        thread::sleep(left.min(Duration::from_secs(60)));
+    }
+    eprintln!("\rTime is up.\x07        ");
+    Ok(())
+}
+
+/// Lists every problem of the newest finished contest in the selected division
+/// with your status, then offers to create starter files for the unsolved ones.
+pub fn run_latest(client: &Client, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let Some(contest) = fetch_division_contests(client, options)?
+        .into_iter()
+        .max_by_key(|contest| (contest.start_time_seconds, contest.id))
+    else {
+        not_found(format_args!(
+            "No finished Codeforces {} contest found.",
+            options.division_label()
+        ));
+    };
+    let problems = fetch_contest_problems(client, contest.id)?;
+    let solved = fetch_user_submissions(client, options)?.solved;
+    let is_solved = |p: &Problem| solved.contains(&(p.contest_id, p.index.clone()));
+
+    if is_json() {
+        let records: Vec<StatusRecord> = problems
+            .iter()
+            .map(|p| StatusRecord {
+                problem: ProblemRecord::from(p),
+                solved: is_solved(p),
+            })
+            .collect();
+        return print_json(&serde_json::json!({
+            "id": contest.id,
+            "name": contest.name,
+            "problems": records,
+        }));
+    }
+    println!("{} ({})", contest.name, contest.id);
+    for p in &problems {
+        println!(
+            "  {:<3} {:>6}  {}  {}",
+            p.index,
+            shown_rating(p),
+            p.name,
+            if is_solved(p) { "solved" } else { "unsolved" }
+        );
+    }
+    let unsolved: Vec<Problem> = problems.iter().filter(|p| !is_solved(p)).cloned().collect();
+    if unsolved.is_empty() {
+        println!("All {} problems solved.", problems.len());
+        return Ok(());
+    }
+
+    if options.dry_run || !io::stdin().is_terminal() {
+        return Ok(());
+    }
+    let question = format!(
+        "Create starter files for the {} unsolved problem(s)?",
+        unsolved.len()
+    );
+    if ask_accept(&question)? == Some(true) {
+        open_batch(&unsolved, options)?;
+    }
+    Ok(())
+}
+
+/// A problem's rating for tables, `-` when unrated.
+fn shown_rating(problem: &Problem) -> String {
+    match problem.rating {
+        0 => "-".to_string(),
+        rating => rating.to_string(),
+    }
+}
+
+/// Lists problems whose name contains `query` (in any case), newest first with
+/// your status, then asks which one to open when stdin is a terminal.
+pub fn run_search(
+    client: &Client,
+    query: &str,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let needle = query.trim().to_lowercase();
+    let mut matches: Vec<Problem> = fetch_all_problems(client, &[])?
+        .into_iter()
+        .filter(|p| p.name.to_lowercase().contains(&needle))
+        .map(UnratedProblem::into_problem)
+        .collect();
+    if matches.is_empty() {
+        not_found(format_args!(
+            "No Codeforces problem name contains '{}'.",
+            query.trim()
+        ));
+    }
+    let solved = fetch_user_submissions(client, options)?.solved;
+    matches.sort_by(|a, b| {
+        b.contest_id
+            .cmp(&a.contest_id)
+            .then_with(|| a.index.cmp(&b.index))
+    });
+    let total = matches.len();
+    matches.truncate(SEARCH_LIMIT);
+    let is_solved = |p: &Problem| solved.contains(&(p.contest_id, p.index.clone()));
+
+    if is_json() {
+        let records: Vec<StatusRecord> = matches
+            .iter()
+            .map(|p| StatusRecord {
+                problem: ProblemRecord::from(p),
+                solved: is_solved(p),
+            })
+            .collect();
+        return print_json(&records);
+    }
+    let name_width = matches
+        .iter()
+        .map(|p| p.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Name".len());
+    println!(
+        "{:>3}  {:>7}  {:<5}  {:>6}  {:<name_width$}  Status",
+        "#", "Contest", "Index", "Rating", "Name"
+    );
+    for (i, p) in matches.iter().enumerate() {
+        println!(
+            "{:>3}  {:>7}  {:<5}  {:>6}  {:<name_width$}  {}",
+            i + 1,
+            p.contest_id,
+            p.index,
+            shown_rating(p),
+            p.name,
+            if is_solved(p) { "solved" } else { "unsolved" }
+        );
+    }
+    if total > matches.len() {
+        println!(
+            "... and {} more; narrow the search to see them.",
+            total - matches.len()
+        );
+    }
+
+    if options.dry_run || !io::stdin().is_terminal() {
+        return Ok(());
+    }
+    if let Some(i) = ask_choice("Open which problem?", matches.len())? {
+        open_problem(&matches[i], options);
+    }
+    Ok(())
+}
+
+/// Picks the same unsolved problem for everyone running it on `date`, so repeated
+/// runs on one day agree.
+pub fn run_daily(
+    client: &Client,
+    level: Option<u32>,
+    date: &str,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    if let Some(level) = level {
+        if !(8..=32).contains(&level) {
+            return Err("Level must be an integer between 8 and 32 inclusive.".into());
+        }
+    }
+
+    let target_rating = level.map(|level| level * 100);
+    let mut candidates = collect_candidates(client, options, |p| {
+        target_rating.is_none_or(|rating| p.rating == rating)
+    })?
+    .candidates;
+    // Problemset order is not guaranteed, so fix one before indexing into it
+    candidates.sort_by(|a, b| {
+        b.contest_id
+            .cmp(&a.contest_id)
+            .then_with(|| a.index.cmp(&b.index))
+    });
+
+    say(options, format_args!("Daily problem for {}", date));
+    if candidates.is_empty() {
+        not_found(format_args!(
+            "No {}problem available for today's pick.",
+            review_prefix(options)
+        ));
+    }
+
+    let mut rng = Rng::from_key(&format!("{}/{}", date, target_rating.unwrap_or(0)));
+    let problem = candidates.swap_remove(rng.below(candidates.len()));
+    open_problem(&problem, options);
+
+    Ok(())
+}
+
+/// How `dist` lays out its counts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DistView {
+    /// One column per index letter A-F.
+    pub by_index: bool,
+    /// `solved/total (percent)` per rating instead of bare totals.
+    pub progress: bool,
+    /// Line width the bars are scaled to; the terminal's when unset.
+    pub width: Option<usize>,
+}
+
+impl DistView {
+    /// Room left for a bar after a `prefix`-wide label, never under 10.
+    fn bar_width(self, prefix: usize) -> usize {
+        self.width
+            .unwrap_or_else(terminal_width)
+            .saturating_sub(prefix)
+            .max(10)
+    }
+}
+
+/// A bar of `value` against the largest `max`, `width` columns at most, with
+/// the first `filled` of `value` drawn as `#` and the rest as `.`.
+fn bar(filled: u32, value: u32, max: u32, width: usize) -> String {
+    let scale = |n: u32| (u64::from(n) * width as u64 / u64::from(max.max(1))) as usize;
+    let length = scale(value).max(usize::from(value > 0));
+    let solid = scale(filled).min(length);
+    format!("{}{}", "#".repeat(solid), ".".repeat(length - solid))
+}
+
+pub fn run_distribution(
+    client: &Client,
+    view: DistView,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let rated_problems = fetch_problem_set(client, &[])?;
+    let divisions = fetch_contest_divisions(client, options)?;
+    if view.by_index {
+        return print_index_distribution(rated_problems, &divisions, options);
+    }
+    if view.progress {
+        return print_progress_distribution(client, rated_problems, &divisions, view, options);
+    }
+
+    let mut distribution: BTreeMap<u32, u32> = BTreeMap::new();
+
+    for problem in rated_problems.into_iter() {
+        if options.accepts_problem(&divisions, &problem) {
+            *distribution.entry(problem.rating).or_insert(0) += 1;
+        }
+    }
+
+    if is_json() {
+        return print_rating_counts(&distribution);
+    }
+    let label = options.division_label();
+    if distribution.is_empty() {
+        not_found(format_args!("No rated Codeforces {label} problems found."));
+    } else {
+        println!("Rating distribution for Codeforces {label} problems:");
+        let max = distribution.values().copied().max().unwrap_or(0);
+        let count_width = max.to_string().len();
+        // `  1400: 523 `
+        let width = view.bar_width(2 + 4 + 2 + count_width + 1);
+        let mut total: u32 = 0;
+        for (rating, count) in &distribution {
+            println!(
+                "  {:>4}: {:>count_width$} {}",
+                rating,
+                count,
+                bar(*count, *count, max, width)
+            );
+            total += *count;
+        }
+        println!("Total problems: {}", total);
+    }
+
+    Ok(())
+}
+
+/// `dist --progress`: per rating, how much of the division you have solved.
+fn print_progress_distribution(
+    client: &Client,
+    problems: Vec<Problem>,
+    divisions: &HashMap<u32, Division>,
+    view: DistView,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let solved = fetch_user_submissions(client, options)?.solved;
+    // rating -> (solved, total)
+    let mut progress: BTreeMap<u32, (u32, u32)> = BTreeMap::new();
+    for problem in problems {
+        if !options.accepts_problem(divisions, &problem) {
+            continue;
+        }
+        let entry = progress.entry(problem.rating).or_insert((0, 0));
+        entry.1 += 1;
+        if solved.contains(&(problem.contest_id, problem.index)) {
+            entry.0 += 1;
+        }
+    }
+
+    if is_json() {
+        let ratings: Vec<RatingCount> = progress
+            .iter()
+            .map(|(&rating, &(solved, count))| RatingCount {
+                rating,
+                count,
+                solved: Some(solved),
+            })
+            .collect();
+        let (solved, total) = progress
+            .values()
+            .fold((0, 0), |(s, t), &(solved, total)| (s + solved, t + total));
+        return print_json(&serde_json::json!({
+            "ratings": ratings,
+            "solved": solved,
+            "total": total,
+        }));
+    }
+    let label = options.division_label();
+    if progress.is_empty() {
+        not_found(format_args!("No rated Codeforces {label} problems found."));
+    }
+    let percent = |solved: u32, total: u32| f64::from(solved) * 100.0 / f64::from(total.max(1));
+    println!("Progress through Codeforces {label} problems:");
+    let max = progress
+        .values()
+        .map(|&(_, total)| total)
+        .max()
+        .unwrap_or(0);
+    let count_width = max.to_string().len();
+    // `  1400: 120/523 ( 23%) `: solved in `#`, the rest in `.`
+    let width = view.bar_width(2 + 4 + 2 + 2 * count_width + 1 + 7 + 1);
+    let (mut all_solved, mut all_total) = (0, 0);
+    for (rating, &(solved, total)) in &progress {
+        println!(
+            "  {:>4}: {:>count_width$}/{:<count_width$} ({:>3.0}%) {}",
+            rating,
+            solved,
+            total,
+            percent(solved, total),
+            bar(solved, total, max, width)
+        );
+        all_solved += solved;
+        all_total += total;
+    }
+    println!(
+        "Total: {}/{} ({:.0}%)",
+        all_solved,
+        all_total,
+        percent(all_solved, all_total)
+    );
+    Ok(())
+}
+
+/// `dist --by-index`: a rating-by-letter table, where `C1` and `C2` count as `C`.
+fn print_index_distribution(
+    problems: Vec<Problem>,
+    divisions: &HashMap<u32, Division>,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut table: BTreeMap<char, BTreeMap<u32, u32>> = BTreeMap::new();
+    for problem in problems {
+        let Some(letter) = problem.index.chars().next() else {
+            continue;
+        };
+        if DIST_INDICES.contains(&letter) && options.accepts_problem(divisions, &problem) {
+            *table
+                .entry(letter)
+                .or_default()
+                .entry(problem.rating)
+                .or_insert(0) += 1;
+        }
+    }
+
+    if is_json() {
+        let columns: Vec<serde_json::Value> = table
+            .iter()
+            .map(|(letter, counts)| {
+                let ratings: Vec<RatingCount> = counts
+                    .iter()
+                    .map(|(&rating, &count)| RatingCount {
+                        rating,
+                        count,
+                        solved: None,
+                    })
+                    .collect();
+                let total: u32 = counts.values().sum();
+                serde_json::json!({ "index": letter.to_string(), "ratings": ratings, "total": total })
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "indices": columns }));
+    }
+    let label = options.division_label();
+    if table.is_empty() {
+        not_found(format_args!("No rated Codeforces {label} problems found."));
+    }
+
+    let ratings: BTreeSet<u32> = table
+        .values()
+        .flat_map(|counts| counts.keys().copied())
+        .collect();
+    println!("Rating distribution by index for Codeforces {label} problems:");
+    let mut header = format!("{:>6}", "Rating");
+    for letter in DIST_INDICES {
+        header.push_str(&format!("{letter:>6}"));
+    }
+    println!("{header}");
+    let cell = |letter: char, rating: u32| {
+        table
+            .get(&letter)
+            .and_then(|counts| counts.get(&rating))
+            .copied()
+            .unwrap_or(0)
+    };
+    for rating in ratings {
+        let mut row = format!("{rating:>6}");
+        for letter in DIST_INDICES {
+            match cell(letter, rating) {
+                0 => row.push_str(&format!("{:>6}", "-")),
+                count => row.push_str(&format!("{count:>6}")),
+            }
+        }
+        println!("{row}");
+    }
+    let mut totals = format!("{:>6}", "Total");
+    for letter in DIST_INDICES {
+        let total: u32 = table.get(&letter).map_or(0, |counts| counts.values().sum());
+        totals.push_str(&format!("{total:>6}"));
+    }
+    println!("{totals}");
+    Ok(())
+}
+
+pub fn run_stats(client: &Client, options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let divisions = fetch_contest_divisions(client, options)?;
+    let passed_problems = fetch_user_submissions(client, options)?.solved_rated;
+
+    let mut stats: BTreeMap<u32, u32> = BTreeMap::new();
+
+    for problem in passed_problems {
+        if options.accepts_problem(&divisions, &problem) {
+            *stats.entry(problem.rating).or_insert(0) += 1;
+        }
+    }
+
+    if is_json() {
+        return print_rating_counts(&stats);
+    }
+    let label = options.division_label();
+    if stats.is_empty() {
+        not_found(format_args!("No solved Codeforces {label} problems found."));
+    } else {
+        println!("Solved problems stats for Codeforces {label}:");
+        let mut total: u32 = 0;
+        let max_count = stats.values().max().unwrap_or(&0);
+        let scale = if *max_count > 50 {
+            50.0 / *max_count as f32
+        } else {
+            1.0
+        };
+
+        for (rating, count) in &stats {
+            let bar_len = (*count as f32 * scale).round() as usize;
+            let bar = "#".repeat(bar_len);
+            println!("{:4}: {:3} | {}", rating, count, bar);
+            total += *count;
+        }
+        println!("Total solved: {}", total);
+    }
+
+    Ok(())
+}
+
+/// Writes `dist`/`stats` counts as `{"ratings": [{"rating", "count"}], "total"}`.
+fn print_rating_counts(counts: &BTreeMap<u32, u32>) -> Result<(), Box<dyn Error>> {
+    let ratings: Vec<RatingCount> = counts
+        .iter()
+        .map(|(&rating, &count)| RatingCount {
+            rating,
+            count,
+            solved: None,
+        })
+        .collect();
+    let total: u32 = counts.values().sum();
+    print_json(&serde_json::json!({ "ratings": ratings, "total": total }))
+}
+
+/// Which contests `run_contests` lists.
+#[derive(Debug, Clone, Copy)]
+pub enum Completion {
+    All,
+    Complete,
+    Incomplete,
+}
+
+pub fn run_contests(
+    client: &Client,
+    completion: Completion,
+    count: Option<usize>,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let rated_problems = fetch_problem_set(client, &[])?;
+    let division_contests = fetch_division_contests(client, options)?;
+    let solved = fetch_user_submissions(client, options)?.solved;
+
+    // contest id -> (solved, total) over rated problems
+    let mut progress: BTreeMap<u32, (u32, u32)> = BTreeMap::new();
+    for problem in rated_problems {
+        let entry = progress.entry(problem.contest_id).or_insert((0, 0));
+        entry.1 += 1;
+        if solved.contains(&(problem.contest_id, problem.index)) {
+            entry.0 += 1;
+        }
+    }
+
+    let mut contests: Vec<(&Contest, u32, u32)> = division_contests
+        .iter()
+        .filter_map(|contest| {
+            progress
+                .get(&contest.id)
+                .map(|&(done, total)| (contest, done, total))
+        })
+        .filter(|&(_, done, total)| match completion {
+            Completion::All => true,
+            Completion::Complete => done == total,
+            Completion::Incomplete => done < total,
+        })
+        .collect();
+    contests.sort_by_key(|&(contest, _, _)| std::cmp::Reverse(contest.id));
+    if let Some(count) = count {
+        contests.truncate(count);
+    }
+
+    if is_json() {
+        let records: Vec<serde_json::Value> = contests
+            .iter()
+            .map(|(contest, done, total)| {
+                serde_json::json!({
+                    "id": contest.id,
+                    "name": contest.name,
+                    "solved": done,
+                    "total": total,
+                })
+            })
+            .collect();
+        return print_json(&records);
+    }
+    if contests.is_empty() {
+        not_found(format_args!(
+            "No matching Codeforces {} contests found.",
+            options.division_label()
+        ));
+    }
+
+    for (contest, done, total) in &contests {
+        println!("{}: {}/{} solved", contest.name, done, total);
+    }
+    println!("Total contests: {}", contests.len());
+
+    Ok(())
+}
+
+pub fn run_index(
+    client: &Client,
+    index_input: &str,
+    options: &PickOptions,
+) -> Result<(), Box<dyn Error>> {
+    let letter = normalize_index(index_input)?;
+
+    let starts_with_letter = |p: &Problem| {
+        p.index
+            .chars()
+            .next()
+            .map(|c| c.to_ascii_uppercase() == letter)
+            .unwrap_or(false)
+    };
+
+    let strategy = Strategy::resolve(options.strategy, &config::settings()?.index_strategy)?;
+    let criterion = format!("index {}", letter);
+    match pick(client, options, strategy, &criterion, starts_with_letter)? {
+        Outcome::Picked(problem) => open_problem(&problem, options),
+        Outcome::Listed => {}
+        Outcome::NotFound => not_found(format_args!(
+            "No {} Codeforces {} '{}' problem found{}.",
+            options.pool_label(),
+            options.division_label(),
+            letter,
+            options.filter_note()
+        )),
+    }
+
+    Ok(())
+}
+
+/// Prints a status line; under `--print-path` or `--json` it goes to stderr so
+/// stdout carries nothing but the path or the JSON document, and `--quiet`
+/// drops it.
+fn say(options: &PickOptions, line: fmt::Arguments) {
+    if is_quiet() {
+        return;
+    }
+    if options.print_path || is_json() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
+/// Reports on stderr that nothing matched and exits with `EXIT_NOT_FOUND`;
+/// under `--quiet` only the exit status says so.
+fn not_found(line: fmt::Arguments) -> ! {
+    if !is_quiet() {
+        eprintln!("{}", line);
+    }
+    report_timings();
+    std::process::exit(EXIT_NOT_FOUND);
+}
+
+fn review_prefix(options: &PickOptions) -> &'static str {
+    if options.review {
+        "solved "
+    } else if options.retry {
+        "attempted "
+    } else {
+        ""
+    }
+}
+
+/// Picks an unsolved problem accepted by `matches` (a solved one in review
+/// mode) using `strategy`. `criterion` describes `matches` for the verbose footer.
+fn pick(
+    client: &Client,
+    options: &PickOptions,
+    strategy: Strategy,
+    criterion: &str,
+    matches: impl Fn(&Problem) -> bool,
+) -> Result<Outcome, Box<dyn Error>> {
+    let pool = collect_candidates(client, options, matches)?;
+    pick_from(pool, options, strategy, criterion)
+}
+
+/// The second half of `pick`, for callers that collected the pool themselves.
+fn pick_from(
+    pool: CandidatePool,
+    options: &PickOptions,
+    strategy: Strategy,
+    criterion: &str,
+) -> Result<Outcome, Box<dyn Error>> {
+    let criterion = &format!("{criterion}{}", options.filter_note());
+    if options.remaining {
+        let left = pool.candidates.len();
+        if let Some(listing) = options.listing {
+            list_candidates(pool.candidates, listing, options.limit)?;
+        }
+        // Keep machine-readable listings parseable on stdout
+        let summary = format!(
+            "{} {} problem(s) left at {}.",
+            left,
+            options.pool_label(),
+            criterion
+        );
+        match options.listing {
+            Some(Listing::Json | Listing::JsonLines) => eprintln!("{}", summary),
+            None if is_json() => print_json(&serde_json::json!({
+                "remaining": left,
+                "criterion": criterion,
+            }))?,
+            _ => println!("{}", summary),
+        }
+        return Ok(Outcome::Listed);
+    }
+    if let Some(listing) = options.listing {
+        return list_candidates(pool.candidates, listing, options.limit);
+    }
+    if is_verbose() {
+        let outcome = if pool.candidates.is_empty() {
+            "nothing to pick".to_string()
+        } else {
+            format!("picked {}", strategy.name())
+        };
+        say(
+            options,
+            format_args!(
+                "Considered {} rated problems from eligible contests, {} matched {}, {} {}, {}.",
+                pool.considered,
+                pool.matched,
+                criterion,
+                pool.candidates.len(),
+                options.pool_label(),
+                outcome
+            ),
+        );
+    }
+    if options.dry_run {
+        say(
+            options,
+            format_args!("{} candidate(s) at {}.", pool.candidates.len(), criterion),
+        );
+    }
+    if options.confirm {
+        return choose_confirmed(pool.candidates, strategy, options);
+    }
+    if let Some(count) = options.count.filter(|&count| count > 1) {
+        let picks = select_many(pool.candidates, count, strategy, options);
+        if picks.is_empty() {
+            return Ok(Outcome::NotFound);
+        }
+        if picks.len() < count {
+            say(
+                options,
+                format_args!("Only {} candidate(s) left at {}.", picks.len(), criterion),
+            );
+        }
+        open_batch(&picks, options)?;
+        return Ok(Outcome::Listed);
+    }
+    Ok(
+        match select(pool.candidates, strategy, options, &mut options.rng()) {
+            Some(problem) => Outcome::Picked(problem),
+            None => Outcome::NotFound,
+        },
+    )
+}
+
+/// Applies `strategy` repeatedly, taking each pick out of the pool, for up to
+/// `count` distinct problems.
+fn select_many(
+    mut candidates: Vec<Problem>,
+    count: usize,
+    strategy: Strategy,
+    options: &PickOptions,
+) -> Vec<Problem> {
+    let mut rng = options.rng();
+    let mut picks = Vec::new();
+    while picks.len() < count {
+        let Some(problem) = select(candidates.clone(), strategy, options, &mut rng) else {
+            break;
+        };
+        candidates.retain(|p| p != &problem);
+        picks.push(problem);
+    }
+    picks
+}
+
+/// Proposes `strategy`'s pick until one is accepted. Rejected problems leave the
+/// pool, so the next proposal is the next-best candidate and nothing repeats.
+fn choose_confirmed(
+    mut candidates: Vec<Problem>,
+    strategy: Strategy,
+    options: &PickOptions,
+) -> Result<Outcome, Box<dyn Error>> {
+    let mut rng = options.rng();
+    let mut rejected = 0;
+    while let Some(problem) = select(candidates.clone(), strategy, options, &mut rng) {
+        eprintln!(
+            "{} ({} {}), rating {}, {} left",
+            problem.name,
+            problem.contest_id,
+            problem.index,
+            problem.rating,
+            candidates.len()
+        );
+        match ask_accept("Solve this one?")? {
+            Some(true) => return Ok(Outcome::Picked(problem)),
+            Some(false) => {
+                rejected += 1;
+                candidates.retain(|p| p != &problem);
+            }
+            None => return Ok(Outcome::Listed),
+        }
+    }
+    if rejected == 0 {
+        return Ok(Outcome::NotFound);
+    }
+    eprintln!("No candidates left after {rejected} rejection(s).");
+    Ok(Outcome::Listed)
+}
+
+/// Prints candidates newest first, ties by index, so output is deterministic.
+/// `limit` keeps only the newest ones.
+fn list_candidates(
+    mut candidates: Vec<Problem>,
+    listing: Listing,
+    limit: Option<usize>,
+) -> Result<Outcome, Box<dyn Error>> {
+    candidates.sort_by(|a, b| {
+        b.contest_id
+            .cmp(&a.contest_id)
+            .then_with(|| a.index.cmp(&b.index))
+    });
+    if let Some(limit) = limit {
+        candidates.truncate(limit);
+    }
+
+    match listing {
+        Listing::Text => {
+            if candidates.is_empty() {
+                return Ok(Outcome::NotFound);
+            }
+            for p in &candidates {
+                println!(
+                    "{:>6} {:<3} {:>4}  {}  {}",
+                    p.contest_id,
+                    p.index,
+                    p.rating,
+                    p.name,
+                    problem_url(p)
+                );
+            }
+        }
+        Listing::Table => {
+            if candidates.is_empty() {
+                return Ok(Outcome::NotFound);
+            }
+            let name_width = candidates
+                .iter()
+                .map(|p| p.name.chars().count())
+                .max()
+                .unwrap_or(0)
+                .max("Name".len());
+            println!(
+                "{:>7}  {:<5}  {:>6}  {:<name_width$}  URL",
+                "Contest", "Index", "Rating", "Name"
+            );
+            for p in &candidates {
+                println!(
+                    "{:>7}  {:<5}  {:>6}  {:<name_width$}  {}",
+                    p.contest_id,
+                    p.index,
+                    p.rating,
+                    p.name,
+                    problem_url(p)
+                );
+            }
+        }
+        Listing::Json => {
+            let records: Vec<ProblemRecord> = candidates.iter().map(ProblemRecord::from).collect();
+            println!("{}", serde_json::to_string_pretty(&records)?);
+        }
+        Listing::JsonLines => {
+            let mut stdout = io::stdout().lock();
+            for p in &candidates {
+                serde_json::to_writer(&mut stdout, &ProblemRecord::from(p))?;
+                writeln!(stdout)?;
+                stdout.flush()?;
+            }
+        }
+    }
+
+    Ok(Outcome::Listed)
+}
+
+fn problem_url(problem: &Problem) -> String {
+    if problem.contest_id >= GYM_FIRST_ID {
+        return format!(
+            "https://codeforces.com/gym/{}/problem/{}",
+            problem.contest_id, problem.index
+        );
+    }
+    format!(
+        "https://codeforces.com/problemset/problem/{}/{}",
+        problem.contest_id, problem.index
+    )
+}
+
+/// The `skip` list; an unreadable one skips nothing, with a warning.
+fn read_skips() -> BTreeSet<String> {
+    skips::read().unwrap_or_else(|err| {
+        warn(format_args!("could not read the skip list: {}", err));
+        BTreeSet::new()
+    })
+}
+
+/// What survived each stage of the candidate filter.
+struct CandidatePool {
+    /// Rated problems from contests in the selected division(s).
+    considered: usize,
+    /// Of those, the ones accepted by the mode's matcher and the index/tag filters.
+    matched: usize,
+    /// `matched` split by rating, for narrowing the pool with `at_rating`.
+    matched_by_rating: HashMap<u32, usize>,
+    /// Matched problems that also pass the solved filters, in problemset order and
+    /// with one copy of each problem mirrored across divisions.
+    candidates: Vec<Problem>,
+    /// Contests you have sent anything to, whatever the verdict.
+    touched: HashSet<u32>,
+}
+
+impl CandidatePool {
+    fn has_rating(&self, rating: u32) -> bool {
+        self.candidates.iter().any(|p| p.rating == rating)
+    }
+
+    /// The same pool restricted to one rating, as if only it had matched.
+    fn at_rating(mut self, rating: u32) -> Self {
+        self.candidates.retain(|p| p.rating == rating);
+        self.matched = self.matched_by_rating.get(&rating).copied().unwrap_or(0);
+        self
+    }
+}
+
+/// Collects every problem from an eligible contest accepted by `matches` that
+/// survives the solved filters.
+fn collect_candidates(
+    client: &Client,
+    options: &PickOptions,
+    matches: impl Fn(&Problem) -> bool,
+) -> Result<CandidatePool, Box<dyn Error>> {
+    // Gym contests have no divisions, so only the contest filters apply to them
+    let (rated_problems, divisions, starts) = if options.gym {
+        (fetch_gym_problems(client, options)?, None, HashMap::new())
+    } else {
+        let rated_problems = fetch_problem_set(client, options.server_tags())?;
+        let contests = fetch_contest_list(client, options)?;
+        let starts = contests
+            .iter()
+            .filter_map(|contest| Some((contest.id, contest.start_time_seconds?)))
+            .collect();
+        (rated_problems, Some(contest_divisions(contests)?), starts)
+    };
+    let rival_solved = |handle: &Option<String>| {
+        handle
+            .as_deref()
+            .map(|handle| fetch_solved_by(client, handle, options))
+            .transpose()
+    };
+    let sources = CandidateSources {
+        problems: rated_problems,
+        divisions,
+        starts,
+        submissions: fetch_user_submissions(client, options)?,
+        skipped: read_skips(),
+        solved_by_other: rival_solved(&options.also_unsolved_by)?,
+        solved_by_rival: rival_solved(&options.solved_by)?,
+    };
+    Ok(filter_candidates(
+        sources,
+        options,
+        &options.excluded_tags()?,
+        matches,
+    ))
+}
+
+/// What `filter_candidates` works from, fetched up front by `collect_candidates`.
+struct CandidateSources {
+    /// Rated problems, or the gym problems with rating 0, in problemset order.
+    problems: Vec<Problem>,
+    /// Division per eligible contest; `None` for gym contests, which have none.
+    divisions: Option<HashMap<u32, Division>>,
+    /// Start time per eligible contest, to tell mirrors from same-named problems.
+    starts: HashMap<u32, u64>,
+    submissions: UserSubmissions,
+    skipped: BTreeSet<String>,
+    /// What the `--also-unsolved-by` and `--solved-by` handles have solved.
+    solved_by_other: Option<HashSet<(u32, String)>>,
+    solved_by_rival: Option<HashSet<(u32, String)>>,
+}
+
+/// The part of `collect_candidates` that needs no network.
+fn filter_candidates(
+    sources: CandidateSources,
+    options: &PickOptions,
+    excluded_tags: &[String],
+    matches: impl Fn(&Problem) -> bool,
+) -> CandidatePool {
+    let CandidateSources {
+        problems: rated_problems,
+        divisions,
+        starts,
+        submissions,
+        skipped,
+        solved_by_other,
+        solved_by_rival,
+    } = sources;
+    let solved = submissions.solved.clone();
+    let attempted = if options.retry {
+        submissions.attempted.clone()
+    } else {
+        HashSet::new()
+    };
+    let touched = submissions.attempted.iter().map(|(id, _)| *id).collect();
+    // Div. 1 and Div. 2 often share a problem under two contest ids; the copy has
+    // the same name and rating, so solving either counts for both
+    let mut solved_mirrors = Mirrors::default();
+    for p in &submissions.solved_rated {
+        solved_mirrors.insert(p);
+    }
+    let excluded = submissions.excluded(options);
+    let mut seen = Mirrors::default();
+
+    let mut pool = CandidatePool {
+        considered: 0,
+        matched: 0,
+        matched_by_rating: HashMap::new(),
+        candidates: Vec::new(),
+        touched,
+    };
+    for p in rated_problems.into_iter() {
+        if divisions
+            .as_ref()
+            .is_some_and(|divisions| !options.accepts_problem(divisions, &p))
+        {
+            continue;
+        }
+        pool.considered += 1;
+        if !matches(&p) || !options.passes_filters(&p, excluded_tags) {
+            continue;
+        }
+        pool.matched += 1;
+        *pool.matched_by_rating.entry(p.rating).or_default() += 1;
+        let key = (p.contest_id, p.index.clone());
+        let mirror_solved = p.rating > 0 && solved_mirrors.contains(&p, &starts);
+        if options.review {
+            if !solved.contains(&key) && !mirror_solved {
+                continue;
+            }
+        } else if options.retry {
+            if !attempted.contains(&key) || excluded.contains(&key) || mirror_solved {
+                continue;
+            }
+        } else if excluded.contains(&key) || mirror_solved {
+            continue;
+        }
+        if solved_by_other
+            .as_ref()
+            .is_some_and(|solved| solved.contains(&key))
+            || solved_by_rival
+                .as_ref()
+                .is_some_and(|solved| !solved.contains(&key))
+        {
+            continue;
+        }
+        if skipped.contains(&format!("{}{}", p.contest_id, p.index)) {
+            continue;
+        }
+        // Offer each mirrored pair once, from the first contest listed
+        if p.rating > 0 {
+            if seen.contains(&p, &starts) {
+                continue;
+            }
+            seen.insert(&p);
+        }
+        pool.candidates.push(p);
+    }
+
+    pool
+}
+
+/// Contest ids per problem name and rating, to recognise a problem that one round
+/// lists under both its Div. 1 and its Div. 2 contest.
+#[derive(Default)]
+struct Mirrors(HashMap<(String, u32), Vec<u32>>);
+
+impl Mirrors {
+    fn insert(&mut self, problem: &Problem) {
+        self.0
+            .entry((problem.name.clone(), problem.rating))
+            .or_default()
+            .push(problem.contest_id);
+    }
+
+    /// Whether `problem`, or its copy in the same round, is already held. A common
+    /// name such as "Game" at one rating in two unrelated rounds is not a mirror.
+    fn contains(&self, problem: &Problem, starts: &HashMap<u32, u64>) -> bool {
+        self.0
+            .get(&(problem.name.clone(), problem.rating))
+            .is_some_and(|ids| {
+                ids.iter()
+                    .any(|&id| same_round(id, problem.contest_id, starts))
+            })
+    }
+}
+
+/// Whether two contest ids belong to one round: parallel divisions get neighbouring
+/// ids or, failing that, share a start time (`starts` maps id to start).
+fn same_round(a: u32, b: u32, starts: &HashMap<u32, u64>) -> bool {
+    a.abs_diff(b) <= 1 || matches!((starts.get(&a), starts.get(&b)), (Some(x), Some(y)) if x == y)
+}
+
+fn select(
+    mut candidates: Vec<Problem>,
+    strategy: Strategy,
+    options: &PickOptions,
+    rng: &mut Rng,
+) -> Option<Problem> {
+    if candidates.is_empty() {
+        return None;
+    }
+    match strategy {
+        Strategy::Newest | Strategy::Oldest => {
+            let ids = candidates.iter().map(|p| p.contest_id);
+            let target = if strategy == Strategy::Newest {
+                ids.max()?
+            } else {
+                ids.min()?
+            };
+            candidates.retain(|p| p.contest_id == target);
+            Some(options.tiebreak.choose(candidates, rng))
+        }
+        Strategy::Random => {
+            // Problemset order is not guaranteed, so fix one for seeded picks
+            candidates.sort_by(|a, b| {
+                b.contest_id
+                    .cmp(&a.contest_id)
+                    .then_with(|| a.index.cmp(&b.index))
+            });
+            let i = rng.below(candidates.len());
+            Some(candidates.swap_remove(i))
+        }
+        Strategy::Variety => {
+            let counts = history::contest_counts(HISTORY_PLATFORM).unwrap_or_else(|err| {
+                warn(format_args!("could not read pick history: {}", err));
+                Default::default()
+            });
+            let picks = |p: &Problem| counts.get(&p.contest_id.to_string()).copied().unwrap_or(0);
+
+            // Fewest prior picks first, then the newest contest
+            let best = candidates
+                .iter()
+                .map(|p| (picks(p), std::cmp::Reverse(p.contest_id)))
+                .min()?;
+            candidates.retain(|p| (picks(p), std::cmp::Reverse(p.contest_id)) == best);
+            let problem = options.tiebreak.choose(candidates, rng);
+            if is_verbose() {
+                say(
+                    options,
+                    format_args!(
+                        "Contest {} was picked {} time(s) before.",
+                        problem.contest_id,
+                        picks(&problem)
+                    ),
+                );
+            }
+            Some(problem)
+        }
+        Strategy::Popular => {
+            let best = candidates.iter().map(|p| p.solved_count).max()?;
+            candidates.retain(|p| p.solved_count == best);
+            Some(options.tiebreak.choose(candidates, rng))
+        }
+    }
+}
+
+/// Prints the pick, creates its starter file, opens it in the browser and hands off to nvim.
+///
+/// Review picks only reuse an existing file and never create a new stub.
+fn open_problem(problem: &Problem, options: &PickOptions) {
+    let url = problem_url(problem);
+    let file_info = prepare_pick(problem, options);
+
+    if options.print_path {
+        let Some((path, _)) = file_info else {
+            eprintln!(
+                "Error: no starter file for {}{}.",
+                problem.contest_id, problem.index
+            );
+            report_timings();
+            std::process::exit(EXIT_FAILURE);
+        };
+        println!("{}", fs::canonicalize(&path).unwrap_or(path).display());
+        return;
+    }
+
+    // Scripts and editor plugins open things themselves
+    if is_json() {
+        if let Err(err) = print_json(&PickRecord::new(problem, file_info.as_ref())) {
+            eprintln!("Error: {}", err);
+            std::process::exit(EXIT_FAILURE);
+        }
+        return;
+    }
+
+    let opened = !options.dry_run && open_url(&url);
+    if let Some(format) = &options.format {
+        println!("{}", render_pick(format, problem, file_info.as_ref()));
+    } else if is_quiet() {
+        // Just the most useful value: the starter file, else the URL
+        match &file_info {
+            Some((path, _)) => println!("{}", display_path(path)),
+            None => println!("{url}"),
+        }
+    } else {
+        if options.review {
+            println!("Review pick: you have already solved this problem.");
+        }
+        println!(
+            "Problem:   {} ({} {})",
+            problem.name, problem.contest_id, problem.index
+        );
+        match problem.rating {
+            0 => println!("Rating:    unrated"),
+            rating => println!("Rating:    {rating}"),
+        }
+        if !options.spoiler_free && !problem.tags.is_empty() {
+            println!("Tags:      {}", problem.tags.join(", "));
+        }
+        if problem.is_interactive() {
+            println!("Note:      interactive; you need an interactor to test it locally");
+        }
+        if !opened {
+            println!("URL:       {url}");
+        }
+        if let Some((path, created)) = &file_info {
+            println!(
+                "File:      {} ({})",
+                display_path(path),
+                file_status(*created, options.dry_run)
+            );
+        }
+    }
+    if options.dry_run {
+        say(
+            options,
+            format_args!("Dry run: no file written, nothing opened."),
+        );
+        return;
+    }
+    if let Some((path, _)) = file_info {
+        // Get the path to open
+        let file_path = display_path(&path);
+        let command = config::settings()
+            .map(|settings| settings.editor_command(&file_path))
+            .unwrap_or_else(|_| vec!["nvim".to_string(), file_path.clone()]);
+        let (program, args) = command.split_first().expect("editor command has a path");
+
+        // Execute the editor, replacing the current process
+        report_timings();
+        let err = Command::new(program).args(args).exec();
+
+        // If exec() returns, it means it failed to start the editor
+        eprintln!("Error: Failed to execute {}: {}", program, err);
+        std::process::exit(EXIT_CONFIG);
+    }
+}
+
+/// Creates starter files for a `--count` batch and prints one line per pick.
+/// Nothing is opened, so a batch never floods the browser or the editor.
+fn open_batch(problems: &[Problem], options: &PickOptions) -> Result<(), Box<dyn Error>> {
+    let picks: Vec<(&Problem, Option<(PathBuf, bool)>)> = problems
+        .iter()
+        .map(|problem| (problem, prepare_pick(problem, options)))
+        .collect();
+    if is_json() {
+        let records: Vec<PickRecord> = picks
+            .iter()
+            .map(|(problem, file_info)| PickRecord::new(problem, file_info.as_ref()))
+            .collect();
+        return print_json(&records);
+    }
+    for (problem, file_info) in &picks {
+        let url = problem_url(problem);
+        if let Some(format) = &options.format {
+            println!("{}", render_pick(format, problem, file_info.as_ref()));
+        } else if options.print_path || is_quiet() {
+            match file_info {
+                Some((path, _)) if options.print_path => {
+                    println!(
+                        "{}",
+                        fs::canonicalize(path).unwrap_or(path.clone()).display()
+                    )
+                }
+                Some((path, _)) => println!("{}", display_path(path)),
+                None => println!("{url}"),
+            }
+        } else {
+            let file = file_info.as_ref().map_or(String::new(), |(path, created)| {
+                format!(
+                    "  {} ({})",
+                    display_path(path),
+                    file_status(*created, options.dry_run)
+                )
+            });
+            println!(
+                "{:>4}  {} ({} {}){}  {}{}",
+                problem.rating,
+                problem.name,
+                problem.contest_id,
+                problem.index,
+                if problem.is_interactive() {
+                    " [interactive]"
+                } else {
+                    ""
+                },
+                url,
+                file
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Fills a `--format` template for one pick.
+fn render_pick(format: &str, problem: &Problem, file_info: Option<&(PathBuf, bool)>) -> String {
+    let path = file_info.map(|(path, _)| display_path(path));
+    render_format(
+        format,
+        &[
+            ("{name}", problem.name.clone()),
+            ("{rating}", problem.rating.to_string()),
+            ("{url}", problem_url(problem)),
+            ("{path}", path.unwrap_or_default()),
+            ("{contest}", problem.contest_id.to_string()),
+            ("{index}", problem.index.clone()),
+        ],
+    )
+}
+
+fn file_status(created: bool, dry_run: bool) -> &'static str {
+    match (created, dry_run) {
+        (true, true) => "would create",
+        (true, false) => "Created",
+        (false, _) => "Exists",
+    }
+}
+
+/// Creates (or, for review and dry runs, looks up) the pick's starter file and
+/// records it in the history. Returns the path and whether it was (or would be) created.
+fn prepare_pick(problem: &Problem, options: &PickOptions) -> Option<(PathBuf, bool)> {
+    let file_info = if options.review {
+        stub_path(problem, options.name_scheme.as_ref())
+            .ok()
+            .filter(|path| path.exists())
+            .map(|path| (path, false))
+    } else if options.dry_run {
+        // `true` here means the file would be created
+        stub_path(problem, options.name_scheme.as_ref())
+            .ok()
+            .map(|path| {
+                let missing = !path.exists();
+                (path, missing)
+            })
+    } else if options.no_file {
+        None
+    } else {
+        match create_cpp_stub(problem, options.name_scheme.as_ref()) {
+            Ok((path, created)) => Some((path, created)),
+            Err(err) => {
+                warn(format_args!("could not create starter file: {}", err));
+                None
+            }
+        }
+    };
+
+    if options.dry_run {
+        // nothing to record
+    } else if let Err(err) = history::record(
+        HISTORY_PLATFORM,
+        &problem.contest_id.to_string(),
+        &problem.index,
+        &problem.name,
+    ) {
+        warn(format_args!("could not record pick history: {}", err));
+    }
+
+    file_info
+}
+
+pub fn normalize_index(input: &str) -> Result<char, Box<dyn Error>> {
+    let trimmed = input.trim();
+    if trimmed.len() != 1 || !trimmed.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err("Problem index must be a single letter (e.g., A, B, C).".into());
+    }
+    Ok(trimmed.chars().next().unwrap().to_ascii_uppercase())
+}
+
+/// Rated problems; with `tags`, only those carrying all of them (see `fetch_all_problems`).
+fn fetch_problem_set(client: &Client, tags: &[String]) -> Result<Vec<Problem>, Box<dyn Error>> {
+    Ok(fetch_all_problems(client, tags)?
+        .into_iter()
+        .filter(|problem| problem.rating.is_some())
+        .map(UnratedProblem::into_problem)
+        .collect())
+}
+
+/// The problemset, rated or not.
+///
+/// Non-empty `tags` are passed to the API, which then sends only the problems
+/// carrying all of them: a far smaller download than the whole problemset.
+fn fetch_all_problems(
+    client: &Client,
+    tags: &[String],
+) -> Result<Vec<UnratedProblem>, Box<dyn Error>> {
+    let url = problemset_url(tags)?;
+    let _spinner = Spinner::start("Fetching problemset…");
+    let response: ApiResponse<ProblemsetResult> = fetch_json_cached(client, url.as_str())?;
+    Ok(problemset_problems(response.result, tags)?)
+}
+
+/// The problems of a `problemset.problems` answer, with their solved counts.
+fn problemset_problems(
+    result: ProblemsetResult,
+    tags: &[String],
+) -> Result<Vec<UnratedProblem>, CfLvlError> {
+    let mut problems = result.problems;
+    // A tag combination can genuinely match nothing; the whole set never does
+    if problems.is_empty() && tags.is_empty() {
+        return Err(CfLvlError::EmptyResponse { what: "problemset" });
+    }
+    let solved_counts: HashMap<(u32, String), u32> = result
+        .problem_statistics
+        .into_iter()
+        .map(|stats| ((stats.contest_id, stats.index), stats.solved_count))
+        .collect();
+    for problem in &mut problems {
+        if let Some(&count) = solved_counts.get(&(problem.contest_id, problem.index.clone())) {
+            problem.solved_count = count;
+        }
+    }
+    Ok(problems)
+}
+
+/// The `problemset.problems` URL, with `tags` encoded into the query so that a
+/// space, `&` or `#` in a tag name reaches the API intact. The cache keys on it too.
+fn problemset_url(tags: &[String]) -> Result<reqwest::Url, Box<dyn Error>> {
+    const URL: &str = "https://codeforces.com/api/problemset.problems";
+    Ok(if tags.is_empty() {
+        reqwest::Url::parse(URL)?
+    } else {
+        reqwest::Url::parse_with_params(URL, &[("tags", tags.join(";"))])?
+    })
+}
+
+/// Problems of the `GYM_CONTESTS` most recently started gym contests that pass the
+/// contest filters, with rating 0: gym problems are missing from the problemset.
+fn fetch_gym_problems(
+    client: &Client,
+    options: &PickOptions,
+) -> Result<Vec<Problem>, Box<dyn Error>> {
+    let rules = ContestRules::from_settings()?;
+    let mut contests: Vec<Contest> = fetch_contest_list(client, options)?
+        .into_iter()
+        .filter(|contest| !rules.exclude.matches(&contest.name))
+        .collect();
+    contests.sort_by_key(|contest| std::cmp::Reverse(contest.start_time_seconds));
+    contests.truncate(GYM_CONTESTS);
+
+    let mut problems = Vec::new();
+    for contest in contests {
+        problems.extend(fetch_contest_problems(client, contest.id)?);
+    }
+    Ok(problems)
+}
+
+/// One contest's problems in index order, from its standings (rating 0 when unrated).
+fn fetch_contest_problems(
+    client: &Client,
+    contest_id: u32,
+) -> Result<Vec<Problem>, Box<dyn Error>> {
+    let url = format!(
+        "https://codeforces.com/api/contest.standings?contestId={contest_id}&from=1&count=1"
+    );
+    let _spinner = Spinner::start("Fetching contest problems…");
+    let response: ApiResponse<StandingsResult> = fetch_json_cached(client, &url)?;
+    Ok(response
+        .result
+        .problems
+        .into_iter()
+        .map(UnratedProblem::into_problem)
+        .collect())
+}
+
+/// Maps each counted contest to its division; excluded and unclassified
+/// contests are left out.
+fn fetch_contest_divisions(
+    client: &Client,
+    options: &PickOptions,
+) -> Result<HashMap<u32, Division>, Box<dyn Error>> {
+    contest_divisions(fetch_contest_list(client, options)?)
+}
+
+fn contest_divisions(contests: Vec<Contest>) -> Result<HashMap<u32, Division>, Box<dyn Error>> {
+    let rules = ContestRules::from_settings()?;
+    Ok(contests
+        .into_iter()
+        .filter_map(|contest| rules.classify(&contest.name).map(|d| (contest.id, d)))
+        .collect())
+}
+
+/// Contests in the selected division(s).
+fn fetch_division_contests(
+    client: &Client,
+    options: &PickOptions,
+) -> Result<Vec<Contest>, Box<dyn Error>> {
+    let rules = ContestRules::from_settings()?;
+    Ok(fetch_contest_list(client, options)?
+        .into_iter()
+        .filter(|contest| {
+            rules
+                .classify(&contest.name)
+                .is_some_and(|division| options.accepts_contest(division))
+        })
+        .collect())
+}
+
+/// Fetches the contest list and keeps the `eligible_contests`.
+fn fetch_contest_list(
+    client: &Client,
+    options: &PickOptions,
+) -> Result<Vec<Contest>, Box<dyn Error>> {
+    let url = if options.gym {
+        "https://codeforces.com/api/contest.list?gym=true"
+    } else {
+        "https://codeforces.com/api/contest.list"
+    };
+    let _spinner = Spinner::start("Fetching contest list…");
+    let response: ApiResponse<Vec<Contest>> = fetch_json_cached(client, url)?;
+    Ok(eligible_contests(
+        non_empty_contests(response.result)?,
+        options,
+    ))
+}
+
+/// Keeps only finished contests unless `include_unfinished` is set, so unreleased
+/// problems are never picked, and only those within `options.contests` and
+/// `options.window`.
+fn eligible_contests(contests: Vec<Contest>, options: &PickOptions) -> Vec<Contest> {
+    contests
+        .into_iter()
+        .filter(|contest| options.include_unfinished || contest.phase == "FINISHED")
+        .filter(|contest| options.contests.contains(contest.id))
+        .filter(|contest| match contest.start_time_seconds {
+            Some(start) => options.window.contains(start),
+            None => options.window.is_unbounded(),
+        })
+        .collect()
+}
+
+/// `contests` unless the API sent none at all, which it does only when something is wrong.
+fn non_empty_contests(contests: Vec<Contest>) -> Result<Vec<Contest>, CfLvlError> {
+    if contests.is_empty() {
+        return Err(CfLvlError::EmptyResponse {
+            what: "contest list",
+        });
+    }
+    Ok(contests)
+}
+
+fn fetch_user_rating(client: &Client) -> Result<Option<u32>, Box<dyn Error>> {
+    let url = format!(
+        "https://codeforces.com/api/user.info?handles={}",
+        config::settings()?.codeforces_handle()?
+    );
+    let _spinner = Spinner::start("Fetching user info…");
+    let response: ApiResponse<Vec<UserInfo>> = fetch_json(client, &url)?;
+    Ok(response
+        .result
+        .into_iter()
+        .next()
+        .and_then(|user| user.rating))
+}
+
+fn fetch_handle_submissions(
+    client: &Client,
+    handle: &str,
+) -> Result<Vec<Submission>, Box<dyn Error>> {
+    let url = format!("https://codeforces.com/api/user.status?handle={handle}");
+    let _spinner = Spinner::start(format!("Fetching submissions of {handle}…"));
+    let response: ApiResponse<Vec<Submission>> = fetch_json(client, &url)?;
+    Ok(response.result)
+}
+
+impl UserSubmissions {
+    /// Sorts `submissions` of the main handle; those sent at or after `recent_cutoff`
+    /// also count as recent.
+    fn from_submissions(
+        submissions: Vec<Submission>,
+        options: &PickOptions,
+        recent_cutoff: Option<u64>,
+    ) -> Self {
+        let mut user = UserSubmissions::default();
+        for submission in submissions {
+            if recent_cutoff.is_some_and(|cutoff| {
+                submission
+                    .creation_time_seconds
+                    .is_some_and(|sent| sent >= cutoff)
+            }) {
+                user.recent.insert((
+                    submission.problem.contest_id,
+                    submission.problem.index.clone(),
+                ));
+            }
+            if submission.is_live() {
+                user.participated.insert(submission.problem.contest_id);
+            }
+            let problem = submission.problem;
+            user.attempted
+                .insert((problem.contest_id, problem.index.clone()));
+
+            if options.counts_as_solved(submission.verdict.as_deref()) {
+                user.add_solved(problem);
+            }
+        }
+        user
+    }
+
+    fn add_solved(&mut self, problem: UnratedProblem) {
+        // Unrated problems still count as solved for filtering
+        self.solved
+            .insert((problem.contest_id, problem.index.clone()));
+        if let Some(rating) = problem.rating {
+            self.solved_rated.insert(Problem {
+                contest_id: problem.contest_id,
+                index: problem.index,
+                name: problem.name,
+                rating,
+                tags: problem.tags,
+                solved_count: 0,
+            });
+        }
+    }
+}
+
+/// The `(contest_id, index)` of every problem someone else has solved.
+fn fetch_solved_by(
+    client: &Client,
+    handle: &str,
+    options: &PickOptions,
+) -> Result<HashSet<(u32, String)>, Box<dyn Error>> {
+    Ok(fetch_handle_submissions(client, handle)?
+        .into_iter()
+        .filter(|submission| options.counts_as_solved(submission.verdict.as_deref()))
+        .map(|submission| (submission.problem.contest_id, submission.problem.index))
+        .collect())
+}
+
+/// Submissions of `codeforces.handle`, plus whatever `codeforces.alt_handles`
+/// solved: alts only add to the solved sets.
+fn fetch_user_submissions(
+    client: &Client,
+    options: &PickOptions,
+) -> Result<UserSubmissions, Box<dyn Error>> {
+    let settings = config::settings()?;
+    let handle = settings.codeforces_handle()?;
+
+    let recent_cutoff = options
+        .skip_recent_days
+        .map(|days| now_epoch().saturating_sub(days * 24 * 60 * 60));
+    let mut submissions = UserSubmissions::from_submissions(
+        fetch_handle_submissions(client, handle)?,
+        options,
+        recent_cutoff,
+    );
+    for alt in settings.codeforces_alt_handles() {
+        for submission in fetch_handle_submissions(client, alt)? {
+            if options.counts_as_solved(submission.verdict.as_deref()) {
+                submissions.add_solved(submission.problem);
+            }
+        }
+    }
+
+    Ok(submissions)
+}
+
+fn stub_path(problem: &Problem, scheme: Option<&NameScheme>) -> Result<PathBuf, Box<dyn Error>> {
+    let settings = config::settings()?;
+    let file_name = match scheme {
+        Some(scheme) => scheme.file_name(problem),
+        None => NameScheme::parse(&settings.name_scheme.value)
+            .map_err(|err| format!("codeforces.name_scheme: {err}"))?
+            .file_name(problem),
+    };
+    Ok(settings.codeforces_dir.value.join(file_name))
+}
+
+fn create_cpp_stub(
+    problem: &Problem,
+    scheme: Option<&NameScheme>,
+) -> Result<(PathBuf, bool), Box<dyn Error>> {
+    let path = stub_path(problem, scheme)?;
+    let starter = r#"#include <iostream>
+
+void solve();
+
+int main() {
+    int count_test_cases;
+
+    std::cin >> count_test_cases;
+
+    while (count_test_cases--) {
+        solve();
+    }
+}
+
+void solve() {
+}
+"#;
+
+    let created = write_starter(&path, starter)?;
+    Ok((path, created))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn problem(contest_id: u32, index: &str, name: &str, rating: u32) -> Problem {
+        Problem {
+            contest_id,
+            index: index.to_string(),
+            rating,
+            name: name.to_string(),
+            tags: Vec::new(),
+            solved_count: 0,
+        }
+    }
+
+    #[test]
+    fn mirrors_need_the_same_round() {
+        let starts = HashMap::from([(1900, 100), (1901, 100), (1950, 100), (1960, 200)]);
+        let mut mirrors = Mirrors::default();
+        mirrors.insert(&problem(1901, "C", "Game", 1500));
+
+        // The Div. 1 copy next door, and a far-off id that starts at the same time
+        assert!(mirrors.contains(&problem(1900, "A", "Game", 1500), &starts));
+        assert!(mirrors.contains(&problem(1950, "A", "Game", 1500), &starts));
+        // An unrelated round, a different rating and a different name
+        assert!(!mirrors.contains(&problem(1960, "B", "Game", 1500), &starts));
+        assert!(!mirrors.contains(&problem(1900, "A", "Game", 1600), &starts));
+        assert!(!mirrors.contains(&problem(1900, "A", "Array", 1500), &starts));
+        // Without start times only neighbouring ids count
+        assert!(!mirrors.contains(&problem(1950, "A", "Game", 1500), &HashMap::new()));
+    }
+
+    #[test]
+    fn tiebreaks_choose_between_problems_of_one_contest() {
+        let ties = || {
+            vec![
+                problem(1900, "D2", "Hard", 2100),
+                problem(1900, "D1", "Easy", 1900),
+            ]
+        };
+        let mut rng = Rng::seeded(7);
+        assert_eq!(Tiebreak::Index.choose(ties(), &mut rng).index, "D1");
+        let ties_by_rating = vec![
+            problem(1900, "D1", "Hard", 2100),
+            problem(1900, "D2", "Easy", 1900),
+        ];
+        assert_eq!(
+            Tiebreak::Rating.choose(ties_by_rating, &mut rng).index,
+            "D2"
+        );
+
+        // A seeded pick ignores the order the ties came in
+        let expected = ["D1", "D2"][Rng::seeded(7).below(2)];
+        let mut reversed = ties();
+        reversed.reverse();
+        assert_eq!(
+            Tiebreak::Random.choose(ties(), &mut Rng::seeded(7)).index,
+            expected
+        );
+        assert_eq!(
+            Tiebreak::Random.choose(reversed, &mut Rng::seeded(7)).index,
+            expected
+        );
+    }
+
+    #[test]
+    fn unrated_accepted_problems_count_as_solved() {
+        let submissions: Vec<Submission> = serde_json::from_str(
+            r#"[
+                {"problem": {"contestId": 1900, "index": "A", "name": "New"}, "verdict": "OK"},
+                {"problem": {"contestId": 1900, "index": "B", "name": "Tried", "rating": 800},
+                 "verdict": "WRONG_ANSWER"}
+            ]"#,
+        )
+        .unwrap();
+        let options = PickOptions::default();
+        let submissions = UserSubmissions::from_submissions(submissions, &options, None);
+        assert!(submissions.solved.contains(&(1900, "A".to_string())));
+        assert!(submissions.solved_rated.is_empty());
+
+        // The problemset has rated 1900A since, but it stays solved
+        let sources = CandidateSources {
+            problems: vec![
+                problem(1900, "A", "New", 800),
+                problem(1900, "B", "Tried", 800),
+                problem(1900, "C", "Other", 900),
+            ],
+            divisions: None,
+            starts: HashMap::new(),
+            submissions,
+            skipped: BTreeSet::new(),
+            solved_by_other: None,
+            solved_by_rival: None,
+        };
+        let pool = filter_candidates(sources, &options, &[], |_| true);
+        let indices: Vec<&str> = pool.candidates.iter().map(|p| p.index.as_str()).collect();
+        assert_eq!(indices, ["B", "C"]);
+    }
+
+    #[test]
+    fn listings_and_counts_never_pick() {
+        let pool = || CandidatePool {
+            considered: 2,
+            matched: 2,
+            matched_by_rating: HashMap::from([(800, 2)]),
+            candidates: vec![
+                problem(1900, "A", "Game", 800),
+                problem(1901, "A", "Array", 800),
+            ],
+            touched: HashSet::new(),
+        };
+        let listed = |options: PickOptions| {
+            matches!(
+                pick_from(pool(), &options, Strategy::Newest, "rating 800").unwrap(),
+                Outcome::Listed
+            )
+        };
+        assert!(listed(PickOptions {
+            listing: Some(Listing::Table),
+            limit: Some(10),
+            ..PickOptions::default()
+        }));
+        assert!(listed(PickOptions {
+            remaining: true,
+            ..PickOptions::default()
+        }));
+        assert!(matches!(
+            pick_from(pool(), &PickOptions::default(), Strategy::Newest, "rating 800").unwrap(),
+            Outcome::Picked(problem) if problem.contest_id == 1901
+        ));
+    }
+
+    fn problemset(json: &str) -> ProblemsetResult {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn an_empty_problemset_is_an_error_only_without_tags() {
+        let empty = r#"{"problems": [], "problemStatistics": []}"#;
+        assert!(matches!(
+            problemset_problems(problemset(empty), &[]),
+            Err(CfLvlError::EmptyResponse { what: "problemset" })
+        ));
+        let tags = ["2-sat".to_string(), "*special".to_string()];
+        assert!(problemset_problems(problemset(empty), &tags)
+            .unwrap()
+            .is_empty());
+
+        let one = r#"{
+            "problems": [{"contestId": 1900, "index": "A", "name": "Game", "rating": 800}],
+            "problemStatistics": [{"contestId": 1900, "index": "A", "solvedCount": 4321}]
+        }"#;
+        let problems = problemset_problems(problemset(one), &[]).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].solved_count, 4321);
+    }
+
+    #[test]
+    fn an_empty_contest_list_is_an_error() {
+        assert!(matches!(
+            non_empty_contests(Vec::new()),
+            Err(CfLvlError::EmptyResponse {
+                what: "contest list"
+            })
+        ));
+        let contests: Vec<Contest> =
+            serde_json::from_str(r#"[{"id": 1900, "name": "Round", "phase": "FINISHED"}]"#)
+                .unwrap();
+        assert_eq!(non_empty_contests(contests).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn only_finished_contests_unless_unfinished_are_included() {
+        let contests = || -> Vec<Contest> {
+            serde_json::from_str(
+                r#"[
+                    {"id": 1904, "name": "Upcoming", "phase": "BEFORE"},
+                    {"id": 1903, "name": "Live", "phase": "CODING", "startTimeSeconds": 400},
+                    {"id": 1902, "name": "Judging", "phase": "PENDING_SYSTEM_TEST", "startTimeSeconds": 300},
+                    {"id": 1901, "name": "Done", "phase": "FINISHED", "startTimeSeconds": 200}
+                ]"#,
+            )
+            .unwrap()
+        };
+        let ids = |options: &PickOptions| -> Vec<u32> {
+            eligible_contests(contests(), options)
+                .iter()
+                .map(|contest| contest.id)
+                .collect()
+        };
+
+        assert_eq!(ids(&PickOptions::default()), [1901]);
+        let unfinished = PickOptions {
+            include_unfinished: true,
+            ..PickOptions::default()
+        };
+        assert_eq!(ids(&unfinished), [1904, 1903, 1902, 1901]);
+    }
 }
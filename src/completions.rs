@@ -0,0 +1,261 @@
+use crate::help;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            "powershell" | "pwsh" => Ok(Shell::Powershell),
+            other => Err(format!(
+                "Unknown shell '{other}'. Use bash, zsh, fish or powershell."
+            )),
+        }
+    }
+}
+
+/// Words accepted first on the command line.
+const SUBCOMMANDS: &[&str] = &[
+    "pick",
+    "index",
+    "dist",
+    "stats",
+    "daily",
+    "contests",
+    "me",
+    "atcoder",
+    "codeforces",
+    "open",
+    "problem",
+    "search",
+    "upsolve",
+    "latest",
+    "virtual",
+    "skip",
+    "unskip",
+    "list",
+    "init",
+    "config",
+    "doctor",
+    "explain",
+    "completions",
+    "man",
+    "help",
+];
+
+/// Whether `word` is a built-in first word, which an alias may not replace.
+pub fn is_subcommand(word: &str) -> bool {
+    SUBCOMMANDS.contains(&word) || word == "distribution"
+}
+
+/// Every flag any mode understands.
+const FLAGS: &[&str] = &[
+    "--help",
+    "--version",
+    "--index",
+    "--level",
+    "--ratings",
+    "--min-rating",
+    "--max-rating",
+    "--auto",
+    "--first-unsolved",
+    "--submissions",
+    "--date",
+    "--seed",
+    "--complete",
+    "--incomplete",
+    "--count",
+    "--since",
+    "--before",
+    "--pos",
+    "--platform",
+    "--exclude-attempted",
+    "--solved",
+    "--attempted",
+    "--skip-recent",
+    "--newest",
+    "--oldest",
+    "--random",
+    "--variety",
+    "--popular",
+    "--min-solved",
+    "--fallback",
+    "--spoiler-free",
+    "--also-unsolved-by",
+    "--solved-by",
+    "--solved-verdicts",
+    "--division",
+    "--tier",
+    "--no-combined",
+    "--contests",
+    "--gym",
+    "--tags",
+    "--any-tag",
+    "--exclude-tags",
+    "--list",
+    "--json",
+    "--jsonl",
+    "--include-unfinished",
+    "--tiebreak",
+    "--remaining",
+    "--name-scheme",
+    "--print-path",
+    "--dry-run",
+    "--confirm",
+    "--no-interactive",
+    "--format",
+    "--allow-partial",
+    "--portable",
+    "--profile",
+    "--handle",
+    "--codeforces-handle",
+    "--atcoder-handle",
+    "--editor",
+    "--browser",
+    "--no-browser",
+    "--no-file",
+    "--quiet",
+    "--verbose",
+    "--max-requests",
+    "--timings",
+    "--refresh",
+    "--max-age",
+    "--timer",
+    "--by-index",
+    "--progress",
+    "--width",
+];
+
+/// Words after which a fixed set of values is suggested.
+fn value_completions() -> Vec<(&'static [&'static str], String)> {
+    let levels: Vec<String> = (8..=32).map(|level| level.to_string()).collect();
+    vec![
+        (&["-l", "--level", "pick"], levels.join(" ")),
+        (&["-i", "--index", "index"], "A B C D E F G H".to_string()),
+        (&["atcoder", "ac"], "a b c d e f g h me".to_string()),
+        (&["--division"], "1 2 3 4 combined edu global".to_string()),
+        (&["--tiebreak"], "index rating random".to_string()),
+        (&["--platform"], "codeforces atcoder".to_string()),
+        (&["config"], "check list get set".to_string()),
+        (&["completions"], "bash zsh fish powershell".to_string()),
+        (&["help"], help::names().join(" ")),
+    ]
+}
+
+/// Builds the completion script for `shell`.
+pub fn script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash(),
+        // zsh runs the bash function through its bashcompinit layer
+        Shell::Zsh => format!("autoload -U +X bashcompinit && bashcompinit\n{}", bash()),
+        Shell::Fish => fish(),
+        Shell::Powershell => powershell(),
+    }
+}
+
+fn bash() -> String {
+    let mut cases = String::new();
+    for (words, values) in value_completions() {
+        let _ = writeln!(
+            cases,
+            "        {})\n            COMPREPLY=($(compgen -W \"{values}\" -- \"$cur\"))\n            return ;;",
+            words.join("|")
+        );
+    }
+    let levels: Vec<String> = (8..=32).map(|level| level.to_string()).collect();
+    format!(
+        r#"_cf_lvl() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}"
+    local prev="${{COMP_WORDS[COMP_CWORD-1]}}"
+    case "$prev" in
+{cases}    esac
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "{flags}" -- "$cur"))
+    elif [[ $COMP_CWORD -eq 1 ]]; then
+        COMPREPLY=($(compgen -W "{subcommands} {levels}" -- "$cur"))
+    fi
+}}
+complete -F _cf_lvl cf-lvl
+"#,
+        flags = FLAGS.join(" "),
+        subcommands = SUBCOMMANDS.join(" "),
+        levels = levels.join(" "),
+    )
+}
+
+fn fish() -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "complete -c cf-lvl -f -n '__fish_use_subcommand' -a '{}'",
+        SUBCOMMANDS.join(" ")
+    );
+    for (words, values) in value_completions() {
+        let (flags, commands): (Vec<&str>, Vec<&str>) =
+            words.iter().partition(|word| word.starts_with('-'));
+        if !commands.is_empty() {
+            let _ = writeln!(
+                out,
+                "complete -c cf-lvl -f -n '__fish_seen_subcommand_from {}' -a '{values}'",
+                commands.join(" ")
+            );
+        }
+        for flag in flags.iter().filter(|flag| flag.starts_with("--")) {
+            let _ = writeln!(out, "complete -c cf-lvl -l {} -x -a '{values}'", &flag[2..]);
+        }
+    }
+    for flag in FLAGS {
+        let _ = writeln!(out, "complete -c cf-lvl -l {}", &flag[2..]);
+    }
+    for short in ["h", "V", "i", "l", "q", "v"] {
+        let _ = writeln!(out, "complete -c cf-lvl -s {short}");
+    }
+    out
+}
+
+fn powershell() -> String {
+    let mut cases = String::new();
+    for (words, values) in value_completions() {
+        let patterns: Vec<String> = words.iter().map(|word| format!("'{word}'")).collect();
+        let _ = writeln!(
+            cases,
+            "        {{ $_ -in @({}) }} {{ $candidates = '{values}' -split ' '; break }}",
+            patterns.join(", ")
+        );
+    }
+    let levels: Vec<String> = (8..=32).map(|level| level.to_string()).collect();
+    format!(
+        r#"Register-ArgumentCompleter -Native -CommandName cf-lvl -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+    $words = @($commandAst.CommandElements | ForEach-Object {{ $_.ToString() }})
+    if ($wordToComplete) {{ $words = $words[0..($words.Count - 2)] }}
+    $prev = $words[-1]
+    $candidates = @()
+    switch ($prev) {{
+{cases}        default {{
+            if ($wordToComplete -like '-*') {{
+                $candidates = '{flags}' -split ' '
+            }} elseif ($words.Count -eq 1) {{
+                $candidates = '{subcommands} {levels}' -split ' '
+            }}
+        }}
+    }}
+    $candidates | Where-Object {{ $_ -like "$wordToComplete*" }} | ForEach-Object {{
+        [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+    }}
+}}
+"#,
+        flags = FLAGS.join(" "),
+        subcommands = SUBCOMMANDS.join(" "),
+        levels = levels.join(" "),
+    )
+}
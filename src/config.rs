@@ -0,0 +1,1150 @@
+use crate::atcoder as atc;
+use crate::codeforces as cf;
+use crate::error::CfLvlError;
+use crate::utils::{
+    config_dir, expand_command, fetch_status, is_json, portable_dir, print_json,
+    PORTABLE_CONFIG_FILE,
+};
+use reqwest::blocking::Client;
+use std::collections::BTreeMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Environment variables that are not settings of their own.
+const CONFIG_ENV: &str = "CF_LVL_CONFIG";
+const HANDLE_ENV: &str = "CF_LVL_HANDLE";
+const ATCODER_HANDLE_ENV: &str = "CF_LVL_ATCODER_HANDLE";
+const DIR_ENV: &str = "CF_LVL_DIR";
+const EDITOR_ENV: &str = "CF_LVL_EDITOR";
+const BROWSER_ENV: &str = "CF_LVL_BROWSER";
+const PROFILE_ENV: &str = "CF_LVL_PROFILE";
+const PLATFORM_ENV: &str = "CF_LVL_PLATFORM";
+
+/// Older, shorter names for some `env_name` overrides; they win when both are set.
+const ENV_ALIASES: [(&str, &str); 7] = [
+    ("default_platform", PLATFORM_ENV),
+    ("codeforces.handle", HANDLE_ENV),
+    ("codeforces.dir", DIR_ENV),
+    ("editor.command", EDITOR_ENV),
+    ("browser.command", BROWSER_ENV),
+    ("strategy.level", cf::LEVEL_STRATEGY_ENV),
+    ("strategy.index", cf::INDEX_STRATEGY_ENV),
+];
+
+/// Keys `config.toml` may set, as `[table]` plus key; `default_platform` is top-level.
+const KNOWN_KEYS: [&str; 19] = [
+    "default_platform",
+    "codeforces.handle",
+    "codeforces.alt_handles",
+    "atcoder.handle",
+    "codeforces.dir",
+    "atcoder.dir",
+    "codeforces.name_scheme",
+    "codeforces.exclude_tags",
+    "codeforces.level_offset",
+    "codeforces.fallback",
+    "contests.include",
+    "contests.exclude",
+    "contests.include_special",
+    "contests.include_combined",
+    "editor.command",
+    "browser.open",
+    "browser.command",
+    "strategy.level",
+    "strategy.index",
+];
+
+/// Where an effective setting came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File,
+    /// A `[profile.<name>]` table selected with `--profile`.
+    Profile(String),
+    Env(String),
+    /// A command-line flag such as `--codeforces-handle`.
+    Flag(&'static str),
+}
+
+impl Source {
+    pub fn describe(&self) -> String {
+        match self {
+            Source::Default => "default".to_string(),
+            Source::File => "config file".to_string(),
+            Source::Profile(name) => format!("profile {name}"),
+            Source::Env(key) => format!("env {key}"),
+            Source::Flag(flag) => format!("flag {flag}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: Source,
+}
+
+/// Every user-tunable value after resolving defaults and overrides.
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Where `config.toml` is looked for, whether or not it exists.
+    pub path: Option<PathBuf>,
+    /// The profile layered over the file's top-level values, if any.
+    pub profile: Option<String>,
+    /// Platform for arguments without a `codeforces`/`atcoder` prefix.
+    pub default_platform: Setting<String>,
+    pub codeforces_handle: Setting<String>,
+    /// Comma-separated other accounts whose accepted problems count as solved.
+    pub alt_handles: Setting<String>,
+    pub atcoder_handle: Setting<String>,
+    pub codeforces_dir: Setting<PathBuf>,
+    /// Where AtCoder starter files go; none are written when unset.
+    pub atcoder_dir: Setting<Option<PathBuf>>,
+    /// Default `--name-scheme` for starter files.
+    pub name_scheme: Setting<String>,
+    /// Comma-separated tags never to pick, unless `--exclude-tags` replaces them.
+    pub exclude_tags: Setting<String>,
+    /// Rating points added to the rounded rating when no level is given, e.g. `+200`.
+    pub level_offset: Setting<String>,
+    /// `0`, `100` or `200`: how far from an exhausted level a pick may move.
+    pub fallback: Setting<String>,
+    /// `cf::ContestPattern` for contests to count even without a division in the name.
+    pub contest_include: Setting<String>,
+    /// `cf::ContestPattern` for contests never to draw from.
+    pub contest_exclude: Setting<String>,
+    /// `true` or `false`; `true` lets April Fools and unrated rounds count.
+    pub include_special: Setting<String>,
+    /// `true` or `false`; `true` lets Div. 2 picks take A-D of "Div. 1 + Div. 2" rounds.
+    pub include_combined: Setting<String>,
+    /// Program (plus arguments) the starter file is handed to.
+    pub editor: Setting<String>,
+    /// `true` or `false`; `false` prints URLs instead of opening them.
+    pub browser_open: Setting<String>,
+    /// Program (plus arguments) URLs are handed to; empty means the system default.
+    pub browser: Setting<String>,
+    pub level_strategy: Setting<String>,
+    pub index_strategy: Setting<String>,
+}
+
+static SETTINGS: OnceLock<Result<Settings, String>> = OnceLock::new();
+static PROFILE: OnceLock<String> = OnceLock::new();
+static FLAG_OVERRIDES: Mutex<Vec<(&'static str, &'static str, String)>> = Mutex::new(Vec::new());
+
+/// Makes `flag`'s `value` win over every other source for `key`; must run
+/// before the settings are first used.
+pub fn set_flag_override(key: &'static str, flag: &'static str, value: String) {
+    if let Ok(mut overrides) = FLAG_OVERRIDES.lock() {
+        overrides.push((key, flag, value));
+    }
+}
+
+/// Selects a `[profile.<name>]` table; must run before the settings are first used.
+pub fn set_profile(name: String) {
+    let _ = PROFILE.set(name);
+}
+
+/// The settings for this run, loaded on first use.
+pub fn settings() -> Result<&'static Settings, Box<dyn Error>> {
+    SETTINGS
+        .get_or_init(Settings::load)
+        .as_ref()
+        .map_err(|err| {
+            CfLvlError::Config {
+                message: err.clone(),
+            }
+            .into()
+        })
+}
+
+impl Settings {
+    /// Layers the config file over the defaults, then environment overrides on top.
+    fn load() -> Result<Self, String> {
+        let path = config_path();
+        let file = match &path {
+            Some(path) if path.exists() => read_config(path)?,
+            _ => BTreeMap::new(),
+        };
+        let profile = PROFILE
+            .get()
+            .cloned()
+            .or_else(|| env::var(PROFILE_ENV).ok().filter(|name| !name.is_empty()));
+        if let Some(name) = &profile {
+            let prefix = format!("profile.{name}.");
+            if !file.keys().any(|key| key.starts_with(&prefix)) {
+                return Err(format!(
+                    "profile '{name}' is not defined; add a [profile.{name}] table to {}.",
+                    path.as_ref()
+                        .map_or(CONFIG_FILE.to_string(), |p| p.display().to_string())
+                ));
+            }
+        }
+        let layered = |key: &str, fallback: &str| {
+            let from_profile = profile.as_ref().and_then(|name| {
+                file.get(&format!("profile.{name}.{key}"))
+                    .map(|value| Setting {
+                        value: value.clone(),
+                        source: Source::Profile(name.clone()),
+                    })
+            });
+            from_profile.unwrap_or_else(|| match file.get(key) {
+                Some(value) => Setting {
+                    value: value.clone(),
+                    source: Source::File,
+                },
+                None => default(fallback.to_string()),
+            })
+        };
+        // Every key can come from the environment, so no setting misses an override
+        let setting = |key: &str, fallback: &str| {
+            let value = from_env(&env_name(key), layered(key, fallback));
+            match ENV_ALIASES.iter().find(|(name, _)| *name == key) {
+                Some((_, alias)) => from_env(alias, value),
+                None => value,
+            }
+        };
+
+        let dir = setting("codeforces.dir", ".");
+        let atcoder_dir = setting("atcoder.dir", "");
+        Ok(Settings {
+            default_platform: setting("default_platform", "codeforces"),
+            codeforces_handle: from_flag("codeforces.handle", setting("codeforces.handle", "")),
+            alt_handles: setting("codeforces.alt_handles", ""),
+            atcoder_handle: from_flag("atcoder.handle", setting("atcoder.handle", "")),
+            codeforces_dir: Setting {
+                value: expand_home(&dir.value),
+                source: dir.source,
+            },
+            atcoder_dir: Setting {
+                value: (!atcoder_dir.value.is_empty()).then(|| expand_home(&atcoder_dir.value)),
+                source: atcoder_dir.source,
+            },
+            name_scheme: setting("codeforces.name_scheme", "{name}"),
+            exclude_tags: setting("codeforces.exclude_tags", ""),
+            level_offset: setting("codeforces.level_offset", "+200"),
+            fallback: setting("codeforces.fallback", "0"),
+            contest_include: setting("contests.include", ""),
+            contest_exclude: setting("contests.exclude", ""),
+            include_special: setting("contests.include_special", "false"),
+            include_combined: setting("contests.include_combined", "true"),
+            editor: from_flag("editor.command", setting("editor.command", "nvim")),
+            browser_open: from_flag("browser.open", setting("browser.open", "true")),
+            browser: from_flag("browser.command", setting("browser.command", "")),
+            level_strategy: setting("strategy.level", "newest"),
+            index_strategy: setting("strategy.index", "newest"),
+            path,
+            profile,
+        })
+    }
+
+    /// Splits the editor command into words, putting `path` where `{path}`
+    /// appears or at the end otherwise.
+    pub fn editor_command(&self, path: &str) -> Vec<String> {
+        expand_command(&self.editor.value, "{path}", path)
+    }
+
+    /// Whether URLs should be handed to a browser at all.
+    pub fn browser_enabled(&self) -> bool {
+        self.browser_open.value != "false"
+    }
+
+    pub fn codeforces_handle(&self) -> Result<&str, CfLvlError> {
+        self.require_handle(
+            "codeforces.handle",
+            HANDLE_ENV,
+            &self.codeforces_handle.value,
+        )
+    }
+
+    /// `codeforces.alt_handles` split into handles; empty when unset.
+    pub fn codeforces_alt_handles(&self) -> Vec<&str> {
+        self.alt_handles
+            .value
+            .split(',')
+            .map(str::trim)
+            .filter(|handle| !handle.is_empty())
+            .collect()
+    }
+
+    pub fn atcoder_handle(&self) -> Result<&str, CfLvlError> {
+        self.require_handle(
+            "atcoder.handle",
+            ATCODER_HANDLE_ENV,
+            &self.atcoder_handle.value,
+        )
+    }
+
+    fn require_handle<'a>(
+        &self,
+        key: &str,
+        env_key: &str,
+        handle: &'a str,
+    ) -> Result<&'a str, CfLvlError> {
+        let handle = handle.trim();
+        if handle.is_empty() {
+            return Err(CfLvlError::Config {
+                message: format!(
+                    "{key} is not set. Add it to {} or set {env_key}.",
+                    self.describe_path()
+                ),
+            });
+        }
+        Ok(handle)
+    }
+
+    fn describe_path(&self) -> String {
+        match &self.path {
+            Some(path) => path.display().to_string(),
+            None => format!("~/.config/cf-lvl/{CONFIG_FILE}"),
+        }
+    }
+
+    /// Lists `(key, value, source)` for display.
+    fn entries(&self) -> Vec<(&'static str, String, String)> {
+        vec![
+            (
+                "default_platform",
+                self.default_platform.value.clone(),
+                self.default_platform.source.describe(),
+            ),
+            (
+                "codeforces.handle",
+                self.codeforces_handle.value.clone(),
+                self.codeforces_handle.source.describe(),
+            ),
+            (
+                "codeforces.alt_handles",
+                self.alt_handles.value.clone(),
+                self.alt_handles.source.describe(),
+            ),
+            (
+                "atcoder.handle",
+                self.atcoder_handle.value.clone(),
+                self.atcoder_handle.source.describe(),
+            ),
+            (
+                "codeforces.dir",
+                self.codeforces_dir.value.display().to_string(),
+                self.codeforces_dir.source.describe(),
+            ),
+            (
+                "atcoder.dir",
+                self.atcoder_dir
+                    .value
+                    .as_ref()
+                    .map_or("(not set)".to_string(), |dir| dir.display().to_string()),
+                self.atcoder_dir.source.describe(),
+            ),
+            (
+                "codeforces.name_scheme",
+                self.name_scheme.value.clone(),
+                self.name_scheme.source.describe(),
+            ),
+            (
+                "codeforces.exclude_tags",
+                self.exclude_tags.value.clone(),
+                self.exclude_tags.source.describe(),
+            ),
+            (
+                "codeforces.level_offset",
+                self.level_offset.value.clone(),
+                self.level_offset.source.describe(),
+            ),
+            (
+                "codeforces.fallback",
+                self.fallback.value.clone(),
+                self.fallback.source.describe(),
+            ),
+            (
+                "contests.include",
+                self.contest_include.value.clone(),
+                self.contest_include.source.describe(),
+            ),
+            (
+                "contests.exclude",
+                self.contest_exclude.value.clone(),
+                self.contest_exclude.source.describe(),
+            ),
+            (
+                "contests.include_special",
+                self.include_special.value.clone(),
+                self.include_special.source.describe(),
+            ),
+            (
+                "contests.include_combined",
+                self.include_combined.value.clone(),
+                self.include_combined.source.describe(),
+            ),
+            (
+                "editor.command",
+                self.editor.value.clone(),
+                self.editor.source.describe(),
+            ),
+            (
+                "browser.open",
+                self.browser_open.value.clone(),
+                self.browser_open.source.describe(),
+            ),
+            (
+                "browser.command",
+                self.browser.value.clone(),
+                self.browser.source.describe(),
+            ),
+            (
+                "strategy.level",
+                self.level_strategy.value.clone(),
+                self.level_strategy.source.describe(),
+            ),
+            (
+                "strategy.index",
+                self.index_strategy.value.clone(),
+                self.index_strategy.source.describe(),
+            ),
+        ]
+    }
+
+    /// Returns one message per invalid setting. Touches only the filesystem.
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !matches!(
+            self.default_platform.value.to_ascii_lowercase().as_str(),
+            "codeforces" | "cf" | "atcoder" | "ac"
+        ) {
+            problems.push(format!(
+                "default_platform ({}): '{}' is not codeforces or atcoder.",
+                self.default_platform.source.describe(),
+                self.default_platform.value
+            ));
+        }
+
+        for (key, handle) in [
+            ("codeforces.handle", &self.codeforces_handle.value),
+            ("atcoder.handle", &self.atcoder_handle.value),
+        ] {
+            if handle.trim().is_empty() {
+                problems.push(format!("{key} is not set."));
+            }
+        }
+
+        if let Err(message) = check_writable(&self.codeforces_dir.value) {
+            problems.push(format!("codeforces.dir: {message}"));
+        }
+        if let Some(dir) = &self.atcoder_dir.value {
+            if let Err(message) = check_writable(dir) {
+                problems.push(format!("atcoder.dir: {message}"));
+            }
+        }
+
+        if let Err(err) = cf::NameScheme::parse(&self.name_scheme.value) {
+            problems.push(format!("codeforces.name_scheme: {err}"));
+        }
+
+        if !self.exclude_tags.value.trim().is_empty() {
+            if let Err(err) = cf::parse_tags(&self.exclude_tags.value) {
+                problems.push(format!("codeforces.exclude_tags: {err}"));
+            }
+        }
+
+        if let Err(err) = cf::parse_level_offset(&self.level_offset.value) {
+            problems.push(format!("codeforces.level_offset: {err}"));
+        }
+
+        if let Err(err) = cf::parse_fallback(&self.fallback.value) {
+            problems.push(format!("codeforces.fallback: {err}"));
+        }
+
+        for (key, pattern) in [
+            ("contests.include", &self.contest_include.value),
+            ("contests.exclude", &self.contest_exclude.value),
+        ] {
+            if let Err(err) = cf::ContestPattern::parse(pattern) {
+                problems.push(format!("{key}: {err}"));
+            }
+        }
+
+        if self.editor.value.trim().is_empty() {
+            problems.push("editor.command is empty.".to_string());
+        }
+
+        if !matches!(self.browser_open.value.as_str(), "true" | "false") {
+            problems.push(format!(
+                "browser.open must be true or false, not '{}'.",
+                self.browser_open.value
+            ));
+        }
+
+        for (key, setting) in [
+            ("contests.include_special", &self.include_special),
+            ("contests.include_combined", &self.include_combined),
+        ] {
+            if !matches!(setting.value.as_str(), "true" | "false") {
+                problems.push(format!(
+                    "{key} must be true or false, not '{}'.",
+                    setting.value
+                ));
+            }
+        }
+
+        for (key, strategy) in [
+            ("strategy.level", &self.level_strategy),
+            ("strategy.index", &self.index_strategy),
+        ] {
+            if let Err(err) = cf::Strategy::from_name(&strategy.value) {
+                problems.push(format!("{key} ({}): {err}", strategy.source.describe()));
+            }
+        }
+
+        problems
+    }
+}
+
+/// Prints the effective settings and any validation failures, returning whether
+/// everything checked out.
+pub fn run_check() -> bool {
+    let settings = match settings() {
+        Ok(settings) => settings,
+        Err(err) => {
+            eprintln!("Problems:");
+            eprintln!("  - {err}");
+            return false;
+        }
+    };
+
+    match &settings.path {
+        Some(path) if path.exists() => println!("Config file: {}", path.display()),
+        _ => println!("Config file: {} (not found)", settings.describe_path()),
+    }
+    if let Some(profile) = &settings.profile {
+        println!("Profile:     {profile}");
+    }
+    println!("Effective settings:");
+    print_entries(settings);
+    println!("Environment overrides:");
+    print_env_names();
+
+    let problems = settings.validate();
+    if problems.is_empty() {
+        println!("All settings are valid.");
+        return true;
+    }
+
+    eprintln!("Problems:");
+    for problem in &problems {
+        eprintln!("  - {problem}");
+    }
+    false
+}
+
+/// Hosts `doctor` checks, with the URL probed for each.
+const REACHABILITY: [(&str, &str); 2] = [
+    (
+        "codeforces.com",
+        "https://codeforces.com/api/contest.list?gym=false",
+    ),
+    (
+        "kenkoooo.com",
+        "https://kenkoooo.com/atcoder/resources/contests.json",
+    ),
+];
+
+/// `doctor`: validates the settings, probes both APIs and the configured
+/// handles, and prints a fix next to every failure. Returns whether all passed.
+pub fn run_doctor(client: &Client) -> bool {
+    let mut failures = 0;
+    let mut report = |ok: bool, what: &str, fix: &str| {
+        if ok {
+            println!("[ok]   {what}");
+        } else {
+            failures += 1;
+            println!("[fail] {what}");
+            println!("       fix: {fix}");
+        }
+    };
+
+    let settings = match settings() {
+        Ok(settings) => settings,
+        Err(err) => {
+            report(
+                false,
+                &err.to_string(),
+                "correct the config file, or run `cf-lvl init` to write a new one",
+            );
+            return false;
+        }
+    };
+
+    let has_file = settings.path.as_deref().is_some_and(Path::exists);
+    let (what, fix) = if has_file {
+        (
+            format!("config file {}", settings.describe_path()),
+            "run `cf-lvl config set <key> <value>` (see `cf-lvl config list`)",
+        )
+    } else {
+        (
+            format!(
+                "no config file at {}; using defaults and environment",
+                settings.describe_path()
+            ),
+            "run `cf-lvl init` to write a config file",
+        )
+    };
+    report(true, &what, "");
+    let problems = settings.validate();
+    for problem in &problems {
+        report(false, problem, fix);
+    }
+    if problems.is_empty() {
+        report(true, "settings are valid", "");
+    }
+
+    let mut reachable = Vec::new();
+    for (host, url) in REACHABILITY {
+        match fetch_status(client, url) {
+            Ok(status) if status < 500 => {
+                reachable.push(host);
+                report(true, &format!("{host} is reachable"), "");
+            }
+            Ok(status) => report(
+                false,
+                &format!("{host} answered HTTP {status}"),
+                "the site may be down; try again later",
+            ),
+            Err(err) => report(
+                false,
+                &format!("{host} is unreachable: {err}"),
+                "check your connection, or HTTPS_PROXY if you are behind a proxy",
+            ),
+        }
+    }
+
+    let mut handles: Vec<(&str, &str, &str, HandleCheck)> = vec![
+        (
+            "codeforces.com",
+            "codeforces.handle",
+            &settings.codeforces_handle.value,
+            cf::user_exists,
+        ),
+        (
+            "kenkoooo.com",
+            "atcoder.handle",
+            &settings.atcoder_handle.value,
+            atc::user_exists,
+        ),
+    ];
+    for alt in settings.codeforces_alt_handles() {
+        handles.push((
+            "codeforces.com",
+            "codeforces.alt_handles",
+            alt,
+            cf::user_exists,
+        ));
+    }
+    for (host, key, handle, user_exists) in handles {
+        let handle = handle.trim();
+        if handle.is_empty() || !reachable.contains(&host) {
+            continue;
+        }
+        let fix = format!("check the spelling, then `cf-lvl config set {key} <user>`");
+        match user_exists(client, handle) {
+            Ok(exists) => report(
+                exists,
+                &format!(
+                    "{key} '{handle}' {}",
+                    if exists { "exists" } else { "does not exist" }
+                ),
+                &fix,
+            ),
+            Err(err) => report(
+                false,
+                &format!("could not check {key} '{handle}': {err}"),
+                "try again later",
+            ),
+        }
+    }
+
+    if failures == 0 {
+        println!("Everything looks good.");
+    } else {
+        println!("{failures} check(s) failed.");
+    }
+    failures == 0
+}
+
+type HandleCheck = fn(&Client, &str) -> Result<bool, Box<dyn Error>>;
+
+/// Each key's environment variable, with its older alias if it has one.
+fn print_env_names() {
+    let width = KNOWN_KEYS.iter().map(|key| key.len()).max().unwrap_or(0);
+    for key in KNOWN_KEYS {
+        match ENV_ALIASES.iter().find(|(name, _)| *name == key) {
+            Some((_, alias)) => println!("  {key:width$}   {} or {alias}", env_name(key)),
+            None => println!("  {key:width$}   {}", env_name(key)),
+        }
+    }
+}
+
+fn print_entries(settings: &Settings) {
+    let entries = settings.entries();
+    let width = entries
+        .iter()
+        .map(|(key, _, _)| key.len())
+        .max()
+        .unwrap_or(0);
+    for (key, value, source) in &entries {
+        println!("  {key:width$} = {value}  ({source})");
+    }
+}
+
+/// `config list`: every effective setting with where it came from.
+pub fn run_list() -> Result<(), Box<dyn Error>> {
+    let settings = settings()?;
+    if is_json() {
+        let entries: Vec<serde_json::Value> = settings
+            .entries()
+            .into_iter()
+            .map(|(key, value, source)| {
+                serde_json::json!({ "key": key, "value": value, "source": source })
+            })
+            .collect();
+        return print_json(&entries);
+    }
+    print_entries(settings);
+    Ok(())
+}
+
+/// `config get <key>`: prints one effective value.
+pub fn run_get(key: &str) -> Result<(), Box<dyn Error>> {
+    if !is_known_key(key) {
+        return Err(unknown_key(key).into());
+    }
+    let settings = settings()?;
+    let value = match settings
+        .entries()
+        .into_iter()
+        .find(|(name, _, _)| *name == key)
+    {
+        Some((_, value, _)) => value,
+        // Profile keys are not part of the effective set; read them from the file
+        None => match settings.path.as_deref().filter(|path| path.exists()) {
+            Some(path) => read_config(path)?.remove(key).unwrap_or_default(),
+            None => String::new(),
+        },
+    };
+    println!("{value}");
+    Ok(())
+}
+
+/// `config set <key> <value>`: writes one key into `config.toml`, keeping every
+/// other line (comments included) as it was.
+pub fn run_set(key: &str, value: &str) -> Result<(), Box<dyn Error>> {
+    if !is_known_key(key) {
+        return Err(unknown_key(key).into());
+    }
+    let path =
+        config_path().ok_or("could not locate a config directory; set HOME or XDG_CONFIG_HOME")?;
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(format!("could not read {}: {err}", path.display()).into()),
+    };
+
+    let updated = set_key(&text, key, value);
+    parse_toml(&updated).map_err(|err| format!("refusing to write {}: {err}", path.display()))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, updated)?;
+    println!("Set {key} = {value} in {}.", path.display());
+    Ok(())
+}
+
+/// Replaces `key`'s line in `text`, or adds it under its table (creating the
+/// table at the end when needed).
+fn set_key(text: &str, key: &str, value: &str) -> String {
+    let (table, leaf) = key.rsplit_once('.').unwrap_or(("", key));
+    let assignment = format!("{leaf} = {}", toml_string(value));
+    let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+
+    let mut current = String::new();
+    // Last line belonging to `table`, where a new key can go
+    let mut table_end = None;
+    for i in 0..lines.len() {
+        let line = lines[i].trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            current = strip_comment(header)
+                .trim_end_matches(']')
+                .trim()
+                .to_string();
+            if current == table {
+                table_end = Some(i);
+            }
+            continue;
+        }
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+        let full = match current.as_str() {
+            "" => name.trim().to_string(),
+            current => format!("{current}.{}", name.trim()),
+        };
+        if full == key {
+            let indent = &lines[i][..lines[i].len() - lines[i].trim_start().len()];
+            lines[i] = format!("{indent}{} = {}", name.trim(), toml_string(value));
+            return lines.join("\n") + "\n";
+        }
+        if current == table {
+            table_end = Some(i);
+        }
+    }
+
+    match table_end {
+        Some(i) => lines.insert(i + 1, assignment),
+        // Top-level keys must come before the first table header
+        None if table.is_empty() => lines.insert(0, assignment),
+        None => {
+            if lines.last().is_some_and(|line| !line.trim().is_empty()) {
+                lines.push(String::new());
+            }
+            if !table.is_empty() {
+                lines.push(format!("[{table}]"));
+            }
+            lines.push(assignment);
+        }
+    }
+    lines.join("\n") + "\n"
+}
+
+fn is_known_key(key: &str) -> bool {
+    if let Some(name) = key.strip_prefix("alias.") {
+        return !name.is_empty() && !name.contains('.');
+    }
+    let key = key
+        .strip_prefix("profile.")
+        .and_then(|rest| rest.split_once('.'))
+        .map_or(key, |(_, key)| key);
+    KNOWN_KEYS.contains(&key)
+}
+
+fn unknown_key(key: &str) -> String {
+    format!(
+        "unknown key '{key}'. Known keys: {} (or profile.<name>.<key>, alias.<name>).",
+        KNOWN_KEYS.join(", ")
+    )
+}
+
+/// Prompts for each setting, checks the Codeforces handle exists and writes
+/// `config.toml`.
+pub fn run_init(client: &Client) -> Result<(), Box<dyn Error>> {
+    let path =
+        config_path().ok_or("could not locate a config directory; set HOME or XDG_CONFIG_HOME")?;
+    if path.exists() && !confirm(&format!("{} exists. Overwrite it?", path.display()))? {
+        println!("Left the existing config untouched.");
+        return Ok(());
+    }
+
+    let codeforces_handle = loop {
+        let handle = prompt("Codeforces handle", None)?;
+        if handle.is_empty() {
+            println!("A Codeforces handle is required.");
+            continue;
+        }
+        match cf::user_exists(client, &handle) {
+            Ok(true) => break handle,
+            Ok(false) => println!("Codeforces has no user '{handle}'. Try again."),
+            Err(err) => {
+                if confirm(&format!(
+                    "Could not verify '{handle}' ({err}). Save it anyway?"
+                ))? {
+                    break handle;
+                }
+            }
+        }
+    };
+    let atcoder_handle = prompt("AtCoder handle", Some(&codeforces_handle))?;
+    let dir = prompt("Directory for Codeforces solutions", Some("."))?;
+    let editor = prompt("Editor command", Some("nvim"))?;
+
+    let text = format!(
+        "[codeforces]\nhandle = {}\ndir = {}\n\n[atcoder]\nhandle = {}\n\n[editor]\ncommand = {}\n",
+        toml_string(&codeforces_handle),
+        toml_string(&dir),
+        toml_string(&atcoder_handle),
+        toml_string(&editor),
+    );
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, text)?;
+    println!("Wrote {}.", path.display());
+    Ok(())
+}
+
+/// Reads one answer from stdin; an empty answer takes `default` when there is one.
+fn prompt(label: &str, default: Option<&str>) -> Result<String, Box<dyn Error>> {
+    match default {
+        Some(default) => print!("{label} [{default}]: "),
+        None => print!("{label}: "),
+    }
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Err("input ended before setup finished".into());
+    }
+    let answer = line.trim();
+    Ok(match default {
+        Some(default) if answer.is_empty() => default.to_string(),
+        _ => answer.to_string(),
+    })
+}
+
+fn confirm(question: &str) -> Result<bool, Box<dyn Error>> {
+    let answer = prompt(&format!("{question} [y/N]"), None)?;
+    Ok(matches!(answer.to_ascii_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Quotes `value` as a TOML basic string.
+fn toml_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn default<T>(value: T) -> Setting<T> {
+    Setting {
+        value,
+        source: Source::Default,
+    }
+}
+
+/// `$CF_LVL_CONFIG` when set, else `config.toml` in the config directory.
+fn config_path() -> Option<PathBuf> {
+    match env::var_os(CONFIG_ENV).filter(|v| !v.is_empty()) {
+        Some(path) => Some(PathBuf::from(path)),
+        None if portable_dir().is_some() => config_dir().map(|dir| dir.join(PORTABLE_CONFIG_FILE)),
+        None => config_dir().map(|dir| dir.join(CONFIG_FILE)),
+    }
+}
+
+/// The `[alias]` expansion for `name`, read straight from the config file: aliases
+/// are expanded before the global flags are applied, so the settings must not load yet.
+/// An unreadable file means no aliases; the error surfaces once a setting is needed.
+pub fn alias(name: &str) -> Option<String> {
+    let path = config_path().filter(|path| path.exists())?;
+    read_config(&path).ok()?.remove(&format!("alias.{name}"))
+}
+
+/// Applies the last `set_flag_override` for `key`, if any.
+fn from_flag(key: &str, fallback: Setting<String>) -> Setting<String> {
+    let overrides = FLAG_OVERRIDES.lock().map(|o| o.clone()).unwrap_or_default();
+    match overrides
+        .into_iter()
+        .rev()
+        .find(|(name, _, _)| *name == key)
+    {
+        Some((_, flag, value)) => Setting {
+            value,
+            source: Source::Flag(flag),
+        },
+        None => fallback,
+    }
+}
+
+/// Lets a non-empty `key` in the environment replace `fallback`.
+fn from_env(key: &str, fallback: Setting<String>) -> Setting<String> {
+    match env::var(key).ok().filter(|value| !value.is_empty()) {
+        Some(value) => Setting {
+            value,
+            source: Source::Env(key.to_string()),
+        },
+        None => fallback,
+    }
+}
+
+/// The environment variable overriding `key`: `CF_LVL_` and the key in capitals,
+/// dots as underscores, e.g. `CF_LVL_CODEFORCES_EXCLUDE_TAGS`.
+pub fn env_name(key: &str) -> String {
+    format!("CF_LVL_{}", key.replace('.', "_").to_ascii_uppercase())
+}
+
+/// Expands a leading `~/` to `$HOME`.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), env::var_os("HOME")) {
+        (Some(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Reads `path` and rejects keys the tool does not know.
+fn read_config(path: &Path) -> Result<BTreeMap<String, String>, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+    let values = parse_toml(&text).map_err(|err| format!("{}: {err}", path.display()))?;
+    if let Some(key) = values.keys().find(|key| !is_known_key(key)) {
+        return Err(format!("{}: {}", path.display(), unknown_key(key)));
+    }
+    Ok(values)
+}
+
+/// Parses the TOML subset the config uses: `[table]` headers, `#` comments and
+/// `key = value` lines whose value is a quoted string, an integer or a boolean.
+/// Keys come back dotted with their table, e.g. `codeforces.handle`.
+fn parse_toml(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    let mut table = String::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let at = |message: String| format!("line {}: {message}", number + 1);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix('[') {
+            let name = strip_comment(header)
+                .strip_suffix(']')
+                .ok_or_else(|| at("unterminated table header".to_string()))?
+                .trim();
+            if !is_bare_key(name) {
+                return Err(at(format!("invalid table name '{name}'")));
+            }
+            table = name.to_string();
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| at("expected 'key = value'".to_string()))?;
+        let key = key.trim();
+        if !is_bare_key(key) {
+            return Err(at(format!("invalid key '{key}'")));
+        }
+        let key = if table.is_empty() {
+            key.to_string()
+        } else {
+            format!("{table}.{key}")
+        };
+        let value = parse_value(value.trim()).map_err(at)?;
+        if values.insert(key.clone(), value).is_some() {
+            return Err(at(format!("duplicate key '{key}'")));
+        }
+    }
+
+    Ok(values)
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key.split('.').all(|part| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        })
+}
+
+/// Drops a trailing `# comment` from an unquoted fragment.
+fn strip_comment(fragment: &str) -> &str {
+    fragment.split('#').next().unwrap_or("").trim()
+}
+
+fn parse_value(raw: &str) -> Result<String, String> {
+    let (value, rest) = if let Some(body) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = body.char_indices();
+        let end = loop {
+            match chars.next() {
+                Some((i, '"')) => break i + 1,
+                Some((_, '\\')) => match chars.next().map(|(_, c)| c) {
+                    Some('"') => value.push('"'),
+                    Some('\\') => value.push('\\'),
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => return Err(format!("unsupported escape '\\{other}'")),
+                    None => return Err("unterminated string".to_string()),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err("unterminated string".to_string()),
+            }
+        };
+        (value, &body[end..])
+    } else if let Some(body) = raw.strip_prefix('\'') {
+        let end = body
+            .find('\'')
+            .ok_or_else(|| "unterminated string".to_string())?;
+        (body[..end].to_string(), &body[end + 1..])
+    } else {
+        let value = strip_comment(raw);
+        let digits = value
+            .strip_prefix(['+', '-'])
+            .unwrap_or(value)
+            .replace('_', "");
+        let is_integer = !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+        if !(is_integer || value == "true" || value == "false") {
+            return Err(format!(
+                "unsupported value '{value}' (quote strings, e.g. \"{value}\")"
+            ));
+        }
+        (value.to_string(), "")
+    };
+
+    if !strip_comment(rest).is_empty() {
+        return Err(format!("unexpected text after value: '{}'", rest.trim()));
+    }
+    Ok(value)
+}
+
+/// Checks that `dir`, or the closest ancestor that already exists, accepts new files.
+fn check_writable(dir: &Path) -> Result<(), String> {
+    let existing = dir
+        .ancestors()
+        .find(|path| path.exists())
+        .ok_or_else(|| format!("no part of {} exists", dir.display()))?;
+    if !existing.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+
+    let probe = existing.join(".cf-lvl-write-test");
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            Ok(())
+        }
+        Err(err) => Err(format!("{} is not writable ({err})", existing.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_key_has_an_environment_name() {
+        assert_eq!(
+            env_name("codeforces.exclude_tags"),
+            "CF_LVL_CODEFORCES_EXCLUDE_TAGS"
+        );
+        assert_eq!(env_name("default_platform"), "CF_LVL_DEFAULT_PLATFORM");
+        for (key, alias) in ENV_ALIASES {
+            assert!(KNOWN_KEYS.contains(&key), "{key}");
+            assert_ne!(env_name(key), alias);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use std::error::Error;
+use std::fmt;
+
+const BODY_SNIPPET_LEN: usize = 200;
+
+/// Nothing matched the request.
+pub const EXIT_NOT_FOUND: i32 = 1;
+/// The command line could not be parsed.
+pub const EXIT_USAGE: i32 = 2;
+/// The config file, a setting or a required handle is missing or invalid.
+pub const EXIT_CONFIG: i32 = 3;
+/// A request failed or an API answered with something unusable.
+pub const EXIT_NETWORK: i32 = 4;
+/// Anything else, e.g. a starter file that could not be written.
+pub const EXIT_FAILURE: i32 = 5;
+
+/// Failures that carry enough context to explain themselves to the user.
+#[derive(Debug)]
+pub enum CfLvlError {
+    /// The server answered with a non-success HTTP status.
+    Http {
+        url: String,
+        status: u16,
+        body: String,
+    },
+    /// The `--max-requests` budget was spent before the operation finished.
+    RequestLimit { limit: usize },
+    /// A catalogue endpoint returned no entries at all, which points at an API
+    /// hiccup rather than a genuine lack of matches.
+    EmptyResponse { what: &'static str },
+    /// A setting could not be loaded or is missing.
+    Config { message: String },
+    /// A problem named on the command line does not exist.
+    NotFound { message: String },
+    /// The Codeforces API answered `FAILED`, with its reason.
+    Api { comment: String },
+}
+
+impl CfLvlError {
+    pub fn http(url: &str, status: u16, body: &str) -> Self {
+        let collapsed: String = body.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut snippet: String = collapsed.chars().take(BODY_SNIPPET_LEN).collect();
+        if collapsed.chars().count() > BODY_SNIPPET_LEN {
+            snippet.push('…');
+        }
+        CfLvlError::Http {
+            url: url.to_string(),
+            status,
+            body: snippet,
+        }
+    }
+}
+
+impl fmt::Display for CfLvlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CfLvlError::Http { url, status, body } if body.is_empty() => {
+                write!(f, "request to {url} failed with HTTP {status}")
+            }
+            CfLvlError::Http { url, status, body } => {
+                write!(f, "request to {url} failed with HTTP {status}: {body}")
+            }
+            CfLvlError::RequestLimit { limit } => write!(
+                f,
+                "request limit of {limit} reached (raise or drop --max-requests to continue)"
+            ),
+            CfLvlError::EmptyResponse { what } => write!(
+                f,
+                "the API returned an empty {what}; this is usually transient, so retry in a moment (add --refresh to bypass the cache)"
+            ),
+            CfLvlError::Config { message } | CfLvlError::NotFound { message } => {
+                write!(f, "{message}")
+            }
+            CfLvlError::Api { comment } => write!(f, "the Codeforces API refused: {comment}"),
+        }
+    }
+}
+
+impl Error for CfLvlError {}
+
+/// Renders `err` and its causes on one line, e.g. a request failure followed by
+/// the DNS error behind it.
+pub fn describe(err: &(dyn Error + 'static)) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        let text = cause.to_string();
+        // Some errors already repeat their cause in their own message
+        if !message.contains(&text) {
+            message.push_str(": ");
+            message.push_str(&text);
+        }
+        source = cause.source();
+    }
+    message.replace('\n', " ")
+}
+
+/// The exit status for a failure that reached `main`.
+pub fn exit_code(err: &(dyn Error + 'static)) -> i32 {
+    if let Some(err) = err.downcast_ref::<CfLvlError>() {
+        return match err {
+            CfLvlError::Config { .. } => EXIT_CONFIG,
+            // A handle the API does not know is a setting to fix, not an outage
+            CfLvlError::Api { comment } if comment.starts_with("handle") => EXIT_CONFIG,
+            CfLvlError::NotFound { .. } => EXIT_NOT_FOUND,
+            CfLvlError::Http { .. }
+            | CfLvlError::RequestLimit { .. }
+            | CfLvlError::EmptyResponse { .. }
+            | CfLvlError::Api { .. } => EXIT_NETWORK,
+        };
+    }
+    // Undecodable responses are API failures too
+    if err.is::<reqwest::Error>() || err.is::<serde_json::Error>() {
+        return EXIT_NETWORK;
+    }
+    EXIT_FAILURE
+}
@@ -0,0 +1,269 @@
+/// `cf-lvl help <topic>` pages; the first name is the one listed.
+const TOPICS: &[(&[&str], &str)] = &[
+    (
+        &["level", "pick"],
+        "cf-lvl [level] | cf-lvl pick [level] | cf-lvl --level N
+Pick an unsolved Codeforces Div. 2 problem rated exactly level * 100.
+
+Levels run from 8 to 32, so level 8 is rating 800, 14 is 1400 and 32 is 3200.
++N and -N pick relative to your current rating rounded to the nearest 100
+(1449 counts as level 14, 1450 as 15). With no level at all, the offset comes
+from codeforces.level_offset in rating points (default +200), kept within
+800-3200.
+
+Flags:
+  --index X            Also require this problem index (A, B, ...)
+  --tags a,b           Also require every listed tag
+  --any-tag            With --tags, require just one of them
+  --exclude-tags a,b   Skip problems carrying any listed tag
+                       (default codeforces.exclude_tags)
+  --no-interactive     Skip interactive problems, which are marked otherwise
+  --newest | --oldest | --random | --variety | --popular
+                       Strategy (default strategy.level, else newest)
+  --seed N             Repeat the same --random pick
+  --min-solved N       Only problems at least N people have solved
+  --fallback 100|200   When the level is used up, try ratings this close
+                       (nearest first; default codeforces.fallback, else 0)
+  --division 1|2|3|4|combined|edu|global, --tier
+                       Contests to draw from (default Div. 2, which
+                       includes Educational rounds and A-D of combined
+                       Div. 1 + Div. 2 rounds)
+  --no-combined        Leave those combined rounds out
+                       (default contests.include_combined = true)
+  --contests A..B      Only contest ids A to B; either side may be left out
+  --since, --before    Contests started in a window: 2021, 30d, 6m or a date
+  --count N            Pick N problems at once; nothing is opened
+  --solved             Review a solved problem instead
+  --also-unsolved-by H Skip what handle H has solved as well
+  --solved-by H        Only problems handle H has solved
+  --attempted          Finish a problem you submitted to but never solved;
+                       alone (cf-lvl --attempted) it looks at every rating
+  --skip-recent N      Skip anything you submitted to in the last N days
+  --list, --remaining  Print or count the candidates instead of picking
+  --dry-run            Show the pick without creating or opening anything
+  --spoiler-free       Hide the pick's tags, which are printed by default
+
+Examples:
+  cf-lvl                         # 200 above your rating, by default
+  cf-lvl 14                      # a 1400 problem from the newest Div. 2
+  cf-lvl --level 16 --index C --random
+  cf-lvl +2 --count 3            # three problems 200 above your rating
+  cf-lvl 15 --tags dp,greedy --any-tag",
+    ),
+    (
+        &["index"],
+        "cf-lvl index [letter] | cf-lvl --index [letter]
+Pick an unsolved Codeforces Div. 2 problem by index letter, any rating.
+
+Flags:
+  --level N            Also require this level
+  --tags a,b           Also require every listed tag
+  --newest | --oldest | --random | --variety | --popular
+                       Strategy (default strategy.index, else newest)
+  --tiebreak index|rating|random
+                       Choose between D1 and D2 of one contest (default index)
+  --gym                Draw from the 10 most recent gym contests instead;
+                       gym problems are unrated, so only index picks work
+
+Examples:
+  cf-lvl index c
+  cf-lvl -i D --random --tags greedy
+  cf-lvl --gym --index C",
+    ),
+    (
+        &["ratings"],
+        "cf-lvl --ratings R1,R2,... | cf-lvl --min-rating R --max-rating R
+Pick among problems at any of the listed ratings (multiples of 100, 800-3200),
+or at any rating in the range. A missing bound is 800 or 3200.
+
+Examples:
+  cf-lvl --ratings 1300,1500
+  cf-lvl --ratings 1600,1700 --random
+  cf-lvl --min-rating 1300 --max-rating 1500",
+    ),
+    (
+        &["auto"],
+        "cf-lvl --auto
+Pick at your current rating (rounded down to 100) up to 200 above it; unrated
+handles use 800-1000.
+
+Example:
+  cf-lvl --auto --variety",
+    ),
+    (
+        &["first-unsolved"],
+        "cf-lvl --first-unsolved
+Pick from the lowest rating that still has an unsolved problem.
+
+Example:
+  cf-lvl --first-unsolved --division 3",
+    ),
+    (
+        &["daily"],
+        "cf-lvl daily [--level N] [--date YYYY-MM-DD]
+The same unsolved problem all day (UTC), however often you run it. The date
+decides the pick, so strategies, --seed, --list and --count do not apply.
+
+Flags:
+  --level N            Only problems of this level
+  --date YYYY-MM-DD    Another day's pick (default today)
+
+Example:
+  cf-lvl daily --level 15",
+    ),
+    (
+        &["dist", "stats"],
+        "cf-lvl dist [--by-index | --progress] | cf-lvl stats
+dist counts the unsolved candidates per rating, after the division and tag
+filters; stats counts the problems you have solved per rating. With --by-index,
+dist prints one column per index A to F (C1 and C2 count as C), to see which
+letter means which difficulty; with --progress, it shows solved/total (percent)
+per rating instead. Each rating gets a bar scaled to the terminal ($COLUMNS, else
+80 columns) or to --width; --progress fills the solved part with # and the rest
+with dots.
+
+Examples:
+  cf-lvl dist --division 3
+  cf-lvl dist --by-index --contests 1700..
+  cf-lvl dist --progress --tier --width 60
+  cf-lvl stats --json",
+    ),
+    (
+        &["contests"],
+        "cf-lvl contests [--complete|--incomplete] [--count N]
+Solved/total per contest, newest first.
+
+Example:
+  cf-lvl contests --incomplete --count 10",
+    ),
+    (
+        &["list"],
+        "cf-lvl list (level | --index X | --ratings ...) [--count N]
+A table of the newest candidates (default 10); nothing is created or opened.
+
+Examples:
+  cf-lvl list 14
+  cf-lvl list --index D --count 25",
+    ),
+    (
+        &["open", "problem"],
+        "cf-lvl open <id|url> [--no-file] [--dry-run] | cf-lvl cf problem <contest> <index>
+Open one known problem: a Codeforces id like 1851C, an AtCoder id like
+abc250_d or a problem URL. A bare URL works without `open`, too. `problem`
+takes the usual Codeforces output flags (--json, --format, --name-scheme, ...).
+
+Examples:
+  cf-lvl open 1851C
+  cf-lvl cf problem 1851 C --print-path
+  cf-lvl open abc250_d --no-file",
+    ),
+    (
+        &["search"],
+        "cf-lvl codeforces search <words>
+List up to 30 Codeforces problems whose name contains the words (any case),
+newest first and marked solved or unsolved, then ask which one to open. Without
+a terminal, or with --dry-run or --json, it only lists.
+
+Examples:
+  cf-lvl cf search \"two arrays\"
+  cf-lvl cf search permutation --json",
+    ),
+    (
+        &["upsolve"],
+        "cf-lvl codeforces upsolve
+Open the first unsolved problem, by index, of the newest contest you sent a
+submission to during the round. Practice and virtual submissions don't count.
+
+Example:
+  cf-lvl cf upsolve --no-file",
+    ),
+    (
+        &["latest"],
+        "cf-lvl codeforces latest [--division ...]
+List every problem of the newest finished contest (Div. 2 unless --division
+says otherwise) with your status, then offer to create starter files for the
+unsolved ones. Nothing is opened.
+
+Example:
+  cf-lvl cf latest --division 3",
+    ),
+    (
+        &["virtual"],
+        "cf-lvl codeforces virtual [--timer DURATION]
+Pick one unsolved problem for each of A to F, only from contests you have never
+sent anything to, and create their starter files; nothing is opened. The pick
+per letter follows strategy.index (newest by default, so often one whole round).
+
+Flags:
+  --timer 2h           Count down afterwards (90s, 30m, 2h) and ring when done
+
+Examples:
+  cf-lvl cf virtual --timer 2h
+  cf-lvl cf virtual --random --division 3",
+    ),
+    (
+        &["skip", "unskip"],
+        "cf-lvl skip [id|url] | cf-lvl unskip <id|url>
+skip keeps a Codeforces problem out of every later pick, whatever the mode;
+unskip lets it back in. skip alone lists the skipped problems. The list is
+skipped.txt in the data directory, next to the pick history.
+
+Examples:
+  cf-lvl skip 1851C
+  cf-lvl unskip 1851C",
+    ),
+    (
+        &["atcoder", "ac"],
+        "cf-lvl atcoder [letter] | cf-lvl atcoder --pos N | cf-lvl atcoder me
+Pick the newest unsolved AtCoder ABC task with this letter.
+
+Flags:
+  --pos N              Task by position instead (5 = e)
+  --since, --before    Contest window: 30d, 6m, 2y or a date
+  --random             Any unsolved task instead of the newest
+  --seed N             Repeat the same --random pick
+  --allow-partial      Tolerate a failed submissions page after the first
+
+Examples:
+  cf-lvl atcoder d
+  cf-lvl pick --platform ac --index e --since 2y --random",
+    ),
+    (
+        &["config", "init", "doctor"],
+        "cf-lvl init | cf-lvl config check|list|get|set | cf-lvl doctor
+init asks for your handles and writes config.toml. config check validates it,
+config list shows every setting and where it came from, and config get/set
+read or write one key. doctor also checks the network and your handles.
+
+Examples:
+  cf-lvl config set codeforces.dir ~/cp/cf
+  cf-lvl config set alias.grind \"pick --level 15 --random\"",
+    ),
+    (
+        &["explain", "completions", "man"],
+        "cf-lvl explain [args...] | cf-lvl completions [shell] | cf-lvl man
+explain describes what the arguments would do without running anything.
+completions prints a bash, zsh, fish or powershell script; man prints the
+manual page.
+
+Examples:
+  cf-lvl explain 15 --random
+  cf-lvl completions zsh > ~/.zfunc/_cf-lvl
+  cf-lvl man > ~/.local/share/man/man1/cf-lvl.1",
+    ),
+];
+
+/// The help page for `name`, or an error listing the topics.
+pub fn topic(name: &str) -> Result<&'static str, String> {
+    let name = name.trim_start_matches('-').to_ascii_lowercase();
+    TOPICS
+        .iter()
+        .find(|(names, _)| names.contains(&name.as_str()))
+        .map(|(_, text)| *text)
+        .ok_or_else(|| format!("No help for '{name}'. Topics: {}.", names().join(", ")))
+}
+
+/// Every topic name `help` accepts, for completions.
+pub fn names() -> Vec<&'static str> {
+    TOPICS.iter().map(|(names, _)| names[0]).collect()
+}
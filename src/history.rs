@@ -0,0 +1,94 @@
+use crate::utils::{data_dir, env_path, now_epoch, portable_dir};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+const HISTORY_FILE: &str = "history.tsv";
+
+/// One problem handed out by the picker.
+///
+/// Lines are `timestamp, platform, contest, index, name`; only the fields
+/// something reads are kept.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub platform: String,
+    pub contest: String,
+}
+
+/// Appends a pick to the history log as a tab-separated line.
+pub fn record(platform: &str, contest: &str, index: &str, name: &str) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    let name = name.replace(['\t', '\n'], " ");
+    writeln!(
+        file,
+        "{}\t{}\t{}\t{}\t{}",
+        now_epoch(),
+        platform,
+        contest,
+        index,
+        name
+    )
+}
+
+/// Reads every well-formed entry; a missing log is an empty history.
+pub fn read() -> io::Result<Vec<Entry>> {
+    let Some(path) = history_path() else {
+        return Ok(Vec::new());
+    };
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    Ok(contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(5, '\t');
+            fields.next()?.parse::<u64>().ok()?;
+            Some(Entry {
+                platform: fields.next()?.to_string(),
+                contest: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Counts past picks per contest on `platform`.
+pub fn contest_counts(platform: &str) -> io::Result<HashMap<String, usize>> {
+    let mut counts = HashMap::new();
+    for entry in read()? {
+        if entry.platform == platform {
+            *counts.entry(entry.contest).or_insert(0) += 1;
+        }
+    }
+    Ok(counts)
+}
+
+/// Where the log lives, moving one left in the old `~/.local/state/cf-lvl` into
+/// place unless running portably.
+fn history_path() -> Option<PathBuf> {
+    let path = data_dir()?.join(HISTORY_FILE);
+    if !path.exists() && portable_dir().is_none() {
+        let legacy = env_path("XDG_STATE_HOME")
+            .or_else(|| env_path("HOME").map(|home| home.join(".local/state")))
+            .map(|dir| dir.join("cf-lvl").join(HISTORY_FILE));
+        if let Some(legacy) = legacy.filter(|legacy| legacy.exists()) {
+            let moved = path
+                .parent()
+                .is_some_and(|parent| fs::create_dir_all(parent).is_ok())
+                && fs::rename(&legacy, &path).is_ok();
+            if !moved {
+                return Some(legacy);
+            }
+        }
+    }
+    Some(path)
+}
@@ -1,14 +1,154 @@
 mod atcoder;
+mod cache;
 mod codeforces;
+mod completions;
+mod config;
+mod error;
+mod help;
+mod history;
+mod man;
+mod skips;
 mod utils;
 
 use crate::atcoder as atc;
 use crate::codeforces as cf;
-use crate::utils::build_client;
+use crate::utils::{build_client, parse_duration, parse_time_bound, ContestWindow};
+use std::collections::BTreeSet;
 use std::env;
 use std::error::Error;
 use std::process;
+use std::time::Duration;
 
+/// Rows `cf-lvl list` prints without `--count`.
+const DEFAULT_LIST_COUNT: usize = 10;
+
+/// A problem identified by a pasted URL.
+enum ProblemUrl {
+    Codeforces { contest_id: u32, index: String },
+    AtCoder { contest_id: String, task_id: String },
+}
+
+impl ProblemUrl {
+    /// Returns `None` when `arg` does not look like a URL at all.
+    fn parse(arg: &str) -> Option<Result<Self, String>> {
+        let without_scheme = arg
+            .strip_prefix("https://")
+            .or_else(|| arg.strip_prefix("http://"));
+        let looks_like_url = without_scheme.is_some()
+            || ["codeforces.com/", "www.codeforces.com/", "atcoder.jp/"]
+                .iter()
+                .any(|host| arg.starts_with(host));
+        if !looks_like_url {
+            return None;
+        }
+
+        let rest = without_scheme.unwrap_or(arg);
+        let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let segments: Vec<&str> = path
+            .split(['?', '#'])
+            .next()
+            .unwrap_or("")
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let parsed = match host.trim_start_matches("www.") {
+            "codeforces.com" => match segments.as_slice() {
+                ["problemset", "problem", contest, index]
+                | ["contest", contest, "problem", index] => {
+                    contest
+                        .parse()
+                        .ok()
+                        .map(|contest_id| ProblemUrl::Codeforces {
+                            contest_id,
+                            index: index.to_string(),
+                        })
+                }
+                _ => None,
+            },
+            "atcoder.jp" => match segments.as_slice() {
+                ["contests", contest, "tasks", task] => Some(ProblemUrl::AtCoder {
+                    contest_id: contest.to_string(),
+                    task_id: task.to_string(),
+                }),
+                _ => None,
+            },
+            other => {
+                return Some(Err(format!(
+                    "Unrecognized host '{other}'. Paste a codeforces.com or atcoder.jp problem URL."
+                )))
+            }
+        };
+
+        Some(parsed.ok_or_else(|| format!("Could not find a problem in URL '{arg}'.")))
+    }
+
+    /// Parses a URL or a bare id: `1851C` on Codeforces, `abc250_d` on AtCoder.
+    fn parse_id(arg: &str) -> Result<Self, String> {
+        if let Some(parsed) = Self::parse(arg) {
+            return parsed;
+        }
+        let digits = arg.chars().take_while(|c| c.is_ascii_digit()).count();
+        let index = &arg[digits..];
+        let index_ok = index.starts_with(|c: char| c.is_ascii_alphabetic())
+            && index.chars().all(|c| c.is_ascii_alphanumeric());
+        if digits > 0 && index_ok {
+            if let Ok(contest_id) = arg[..digits].parse() {
+                return Ok(ProblemUrl::Codeforces {
+                    contest_id,
+                    index: index.to_ascii_uppercase(),
+                });
+            }
+        }
+        match arg.rsplit_once('_') {
+            Some((contest, task))
+                if !contest.is_empty()
+                    && !task.is_empty()
+                    && arg
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') =>
+            {
+                Ok(ProblemUrl::AtCoder {
+                    contest_id: contest.to_ascii_lowercase(),
+                    task_id: arg.to_ascii_lowercase(),
+                })
+            }
+            _ => Err(format!(
+                "Unrecognized problem '{arg}'. Use a Codeforces id like 1851C, an AtCoder id \
+                 like abc250_d, or a problem URL."
+            )),
+        }
+    }
+
+    /// Wraps the problem in the command opening it with `no_file` and `dry_run`.
+    fn into_command(self, no_file: bool, dry_run: bool) -> Command {
+        match self {
+            ProblemUrl::Codeforces { contest_id, index } => Command::OpenCodeforces {
+                contest_id,
+                index,
+                options: cf::PickOptions {
+                    no_file,
+                    dry_run,
+                    ..Default::default()
+                },
+            },
+            ProblemUrl::AtCoder {
+                contest_id,
+                task_id,
+            } => Command::OpenAtCoder {
+                contest_id,
+                task_id,
+                options: atc::PickOptions {
+                    no_file,
+                    dry_run,
+                    ..Default::default()
+                },
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
 enum Platform {
     Codeforces,
     AtCoder,
@@ -26,107 +166,1845 @@ impl Platform {
     }
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let mut args = env::args().skip(1);
+/// What a Codeforces invocation asks for.
+#[derive(Debug)]
+enum CfMode {
+    Level(u32),
+    /// `+N`/`-N`: N levels above or below the user's rounded rating.
+    Relative(i32),
+    /// No mode at all: the rounded rating plus `codeforces.level_offset`.
+    FromRating,
+    Index(String),
+    Ratings(BTreeSet<u32>),
+    Auto,
+    FirstUnsolved,
+    Daily {
+        level: Option<u32>,
+        date: String,
+    },
+    Distribution(cf::DistView),
+    Stats,
+    /// `search <words>`: problems whose name contains the words.
+    Search(String),
+    /// The newest round you took part in with a problem left.
+    Upsolve,
+    /// Every problem of the newest finished contest in the division.
+    Latest,
+    /// One unsolved A to F from untouched contests, optionally timed.
+    Virtual {
+        timer: Option<std::time::Duration>,
+    },
+    Contests {
+        completion: cf::Completion,
+        count: Option<usize>,
+    },
+}
+
+/// `cf-lvl config ...` subcommands.
+#[derive(Debug)]
+enum ConfigAction {
+    Check,
+    List,
+    Get(String),
+    Set(String, String),
+}
 
-    let first_arg = match args.next() {
-        Some(arg) => arg,
-        None => {
-            print_usage();
-            process::exit(1);
+/// `skip` and `unskip`: the persistent list of problems never to pick.
+#[derive(Debug)]
+enum SkipAction {
+    List,
+    /// A Codeforces id such as `1851C`.
+    Add(String),
+    Remove(String),
+}
+
+/// A fully parsed command line, decided before any request is made.
+#[derive(Debug)]
+enum Command {
+    Help,
+    /// `help <topic>`: one mode's page.
+    HelpTopic(&'static str),
+    /// Print the version with the commit and date it was built from.
+    Version,
+    /// No usable arguments; print usage and exit with an error.
+    Usage,
+    Config(ConfigAction),
+    Skip(SkipAction),
+    /// Interactive first-run setup.
+    Init,
+    /// Check settings, network and handles, suggesting fixes.
+    Doctor,
+    /// Print a shell completion script.
+    Completions(completions::Shell),
+    /// Print the roff manual page.
+    Man,
+    /// Describe the wrapped command instead of running it.
+    Explain(Box<Command>),
+    /// Open one known problem, skipping selection.
+    OpenCodeforces {
+        contest_id: u32,
+        index: String,
+        options: cf::PickOptions,
+    },
+    OpenAtCoder {
+        contest_id: String,
+        task_id: String,
+        options: atc::PickOptions,
+    },
+    /// Open the configured user's profile page.
+    Profile {
+        platform: Platform,
+        submissions: bool,
+    },
+    Codeforces {
+        mode: CfMode,
+        options: cf::PickOptions,
+    },
+    AtCoder {
+        index: String,
+        /// The `--pos` the index was resolved from, if any.
+        position: Option<u32>,
+        options: atc::PickOptions,
+    },
+}
+
+fn main() {
+    let result = run();
+    utils::report_timings();
+    if let Err(err) = result {
+        eprintln!("Error: {}", error::describe(err.as_ref()));
+        process::exit(error::exit_code(err.as_ref()));
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut all_args = expand_short_flags(env::args().skip(1).collect());
+    expand_alias(&mut all_args).unwrap_or_else(|err| fail(&err));
+    let globals = take_global_flags(&mut all_args).unwrap_or_else(|err| fail(&err));
+    let json = globals.json;
+    globals.apply();
+    // An unreadable config surfaces later, when a setting is actually needed
+    let default_platform = config::settings()
+        .ok()
+        .and_then(|settings| Platform::from_arg(&settings.default_platform.value).ok())
+        .unwrap_or(Platform::Codeforces);
+    let command = parse(all_args, default_platform, json).unwrap_or_else(|err| fail(&err));
+    execute(command)
+}
+
+/// Global flags that take a value, so `expand_alias` can step over it.
+const GLOBAL_VALUE_FLAGS: &[&str] = &[
+    "--max-requests",
+    "--profile",
+    "--handle",
+    "--codeforces-handle",
+    "--atcoder-handle",
+    "--editor",
+    "--browser",
+    "--max-age",
+];
+
+/// Replaces the first command word with its `[alias]` expansion from the config
+/// file. Built-in commands always win over an alias of the same name.
+fn expand_alias(args: &mut Vec<String>) -> Result<(), String> {
+    let mut at = 0;
+    while at < args.len() && args[at].starts_with('-') {
+        at += if GLOBAL_VALUE_FLAGS.contains(&args[at].as_str()) {
+            2
+        } else {
+            1
+        };
+    }
+    // `explain grind` explains the expansion
+    if args.get(at).is_some_and(|arg| arg == "explain") {
+        at += 1;
+    }
+    let Some(name) = args.get(at) else {
+        return Ok(());
+    };
+    if completions::is_subcommand(name) || Platform::from_arg(name).is_ok() {
+        return Ok(());
+    }
+    // The config file's location depends on it
+    if args.iter().any(|arg| arg == "--portable") {
+        utils::set_portable(true);
+    }
+    if let Some(expansion) = config::alias(name) {
+        let words = expand_short_flags(expansion.split_whitespace().map(str::to_string).collect());
+        if words.is_empty() {
+            return Err(format!("alias '{name}' is empty."));
         }
+        args.splice(at..=at, words);
+    }
+    Ok(())
+}
+
+/// The flags that apply to every mode, wherever they appeared on the command line.
+#[derive(Debug, Default)]
+struct GlobalFlags {
+    quiet: bool,
+    verbose: bool,
+    timings: bool,
+    json: bool,
+    portable: bool,
+    max_requests: Option<usize>,
+    profile: Option<String>,
+    /// `(key, flag, value)` setting overrides, in the order they apply.
+    overrides: Vec<(&'static str, &'static str, String)>,
+    refresh: bool,
+    max_age: Option<Duration>,
+}
+
+/// Strips the flags that apply to every mode from `args`.
+fn take_global_flags(args: &mut Vec<String>) -> Result<GlobalFlags, String> {
+    let mut flags = GlobalFlags {
+        quiet: take_flag(args, "--quiet") | take_flag(args, "-q"),
+        verbose: take_flag(args, "--verbose") | take_flag(args, "-v"),
+        timings: take_flag(args, "--timings"),
+        json: take_flag(args, "--json"),
+        portable: take_flag(args, "--portable"),
+        ..GlobalFlags::default()
     };
+    if let Some(value) = take_value(args, "--max-requests")? {
+        let limit = value
+            .parse::<usize>()
+            .map_err(|_| "--max-requests must be a non-negative integer.")?;
+        flags.max_requests = Some(limit);
+    }
+    flags.profile = take_value(args, "--profile")?;
+    // Pushed first so the platform-specific flags below win over it
+    if let Some(handle) = take_value(args, "--handle")? {
+        flags
+            .overrides
+            .push(("codeforces.handle", "--handle", handle.clone()));
+        flags.overrides.push(("atcoder.handle", "--handle", handle));
+    }
+    for (flag, key) in [
+        ("--codeforces-handle", "codeforces.handle"),
+        ("--atcoder-handle", "atcoder.handle"),
+        ("--editor", "editor.command"),
+        ("--browser", "browser.command"),
+    ] {
+        if let Some(value) = take_value(args, flag)? {
+            flags.overrides.push((key, flag, value));
+        }
+    }
+    if take_flag(args, "--no-browser") {
+        flags
+            .overrides
+            .push(("browser.open", "--no-browser", "false".to_string()));
+    }
+    flags.refresh = take_flag(args, "--refresh");
+    flags.max_age = take_value(args, "--max-age")?
+        .map(|value| parse_duration(&value))
+        .transpose()?;
+    Ok(flags)
+}
 
-    if matches!(first_arg.as_str(), "-h" | "--help" | "help") {
-        print_usage();
-        return Ok(());
+impl GlobalFlags {
+    /// Records the flags in the process-wide state the other modules read.
+    fn apply(self) {
+        utils::set_quiet(self.quiet);
+        utils::set_verbose(self.verbose);
+        utils::set_timings(self.timings);
+        utils::set_json(self.json);
+        utils::set_portable(self.portable);
+        if let Some(limit) = self.max_requests {
+            utils::set_request_limit(limit);
+        }
+        if let Some(name) = self.profile {
+            config::set_profile(name);
+        }
+        for (key, flag, value) in self.overrides {
+            config::set_flag_override(key, flag, value);
+        }
+        cache::set_refresh(self.refresh);
+        if let Some(max_age) = self.max_age {
+            cache::set_max_age(max_age);
+        }
+    }
+}
+
+/// Turns the remaining arguments into a `Command` without side effects. Bare
+/// arguments go to `default_platform`; `json` is the global `--json`.
+fn parse(args: Vec<String>, default_platform: Platform, json: bool) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let Some(first_arg) = args.next() else {
+        return match default_platform {
+            Platform::Codeforces => parse_codeforces(Vec::new(), json),
+            Platform::AtCoder => Ok(Command::Usage),
+        };
+    };
+
+    if first_arg == "help" {
+        if let Some(name) = args.next() {
+            reject_extra(&args.collect::<Vec<_>>())?;
+            return Ok(Command::HelpTopic(help::topic(&name)?));
+        }
+        return Ok(Command::Help);
+    }
+
+    if matches!(first_arg.as_str(), "-h" | "--help") {
+        return Ok(Command::Help);
+    }
+
+    if matches!(first_arg.as_str(), "-V" | "--version") {
+        return Ok(Command::Version);
+    }
+
+    if first_arg == "explain" {
+        return Ok(Command::Explain(Box::new(parse(
+            args.collect(),
+            default_platform,
+            json,
+        )?)));
+    }
+
+    // `dist --help`, `cf upsolve -h`: the subcommand's own page
+    if args
+        .as_slice()
+        .iter()
+        .any(|arg| arg == "-h" || arg == "--help")
+    {
+        let mut words = vec![first_arg];
+        words.extend(args);
+        return Ok(subcommand_help(&words));
+    }
+
+    if first_arg == "init" {
+        return Ok(Command::Init);
     }
 
-    // If the first arg is a known platform, use it; otherwise default to Codeforces and keep the arg.
+    if first_arg == "man" {
+        reject_extra(&args.collect::<Vec<_>>())?;
+        return Ok(Command::Man);
+    }
+
+    if first_arg == "doctor" {
+        return Ok(Command::Doctor);
+    }
+
+    if first_arg == "completions" {
+        return match (args.next(), args.next()) {
+            (Some(shell), None) => Ok(Command::Completions(completions::Shell::from_name(&shell)?)),
+            _ => Err("Usage: cf-lvl completions <bash|zsh|fish|powershell>".to_string()),
+        };
+    }
+
+    if first_arg == "config" {
+        return match args.next().as_deref() {
+            Some("check") => Ok(Command::Config(ConfigAction::Check)),
+            Some("list") => Ok(Command::Config(ConfigAction::List)),
+            Some("get") => match (args.next(), args.next()) {
+                (Some(key), None) => Ok(Command::Config(ConfigAction::Get(key))),
+                _ => Err("Usage: cf-lvl config get <key>".to_string()),
+            },
+            Some("set") => match (args.next(), args.next(), args.next()) {
+                (Some(key), Some(value), None) => {
+                    Ok(Command::Config(ConfigAction::Set(key, value)))
+                }
+                _ => Err("Usage: cf-lvl config set <key> <value>".to_string()),
+            },
+            _ => Err("Usage: cf-lvl config <check|list|get|set>".to_string()),
+        };
+    }
+
+    if first_arg == "skip" || first_arg == "unskip" {
+        let rest: Vec<String> = args.collect();
+        let id = match rest.as_slice() {
+            [] if first_arg == "skip" => return Ok(Command::Skip(SkipAction::List)),
+            [id] => {
+                let parsed = ProblemUrl::parse(id).unwrap_or_else(|| ProblemUrl::parse_id(id))?;
+                match parsed {
+                    ProblemUrl::Codeforces { contest_id, index } => {
+                        format!("{contest_id}{}", index.to_ascii_uppercase())
+                    }
+                    ProblemUrl::AtCoder { .. } => {
+                        return Err(format!(
+                            "Only Codeforces problems can be skipped, not '{id}'."
+                        ))
+                    }
+                }
+            }
+            _ => return Err(format!("Usage: cf-lvl {first_arg} <problem-id|url>")),
+        };
+        return Ok(Command::Skip(if first_arg == "skip" {
+            SkipAction::Add(id)
+        } else {
+            SkipAction::Remove(id)
+        }));
+    }
+
+    if first_arg == "pick" {
+        return parse_pick(args.collect(), default_platform, json);
+    }
+
+    if first_arg == "list" {
+        let mut rest: Vec<String> = args.collect();
+        let count = take_value(&mut rest, "--count")?
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| "--count must be a non-negative integer.")
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_LIST_COUNT);
+        rest.push("--list".to_string());
+        return match parse_codeforces(rest, json)? {
+            Command::Codeforces {
+                mode:
+                    mode @ (CfMode::Level(_)
+                    | CfMode::Relative(_)
+                    | CfMode::FromRating
+                    | CfMode::Index(_)
+                    | CfMode::Ratings(_)
+                    | CfMode::Auto
+                    | CfMode::FirstUnsolved),
+                mut options,
+            } => {
+                if options.listing == Some(cf::Listing::Text) {
+                    options.listing = Some(cf::Listing::Table);
+                }
+                options.limit = Some(count);
+                Ok(Command::Codeforces { mode, options })
+            }
+            _ => Err(
+                "Usage: cf-lvl list (<level> | --index X | --ratings ...) [--count N]".to_string(),
+            ),
+        };
+    }
+
+    if first_arg == "open" {
+        let mut rest: Vec<String> = args.collect();
+        let no_file = take_flag(&mut rest, "--no-file");
+        let dry_run = take_flag(&mut rest, "--dry-run");
+        return match rest.as_slice() {
+            [id] => Ok(ProblemUrl::parse_id(id)?.into_command(no_file, dry_run)),
+            _ => Err("Usage: cf-lvl open <problem-id|url> [--no-file] [--dry-run]".to_string()),
+        };
+    }
+
+    if let Some(parsed) = ProblemUrl::parse(&first_arg) {
+        return Ok(parsed?.into_command(false, false));
+    }
+
+    // If the first arg is a known platform, use it; otherwise use the default and keep the arg.
     let (platform, rest): (Platform, Vec<String>) = match Platform::from_arg(&first_arg) {
         Ok(p) => (p, args.collect()),
         Err(_) => {
             let mut collected: Vec<String> = Vec::new();
             collected.push(first_arg);
             collected.extend(args);
-            (Platform::Codeforces, collected)
+            (default_platform, collected)
         }
     };
 
-    let client = build_client()?;
+    match platform {
+        Platform::Codeforces => parse_codeforces(rest, json),
+        Platform::AtCoder => parse_atcoder(rest),
+    }
+}
 
+/// `pick [--platform cf|ac] (--level N | --index X | N | X) ...`: one entry point
+/// for both platforms, handing the remaining arguments to the platform's parser.
+fn parse_pick(
+    mut rest: Vec<String>,
+    default_platform: Platform,
+    json: bool,
+) -> Result<Command, String> {
+    let platform = take_value(&mut rest, "--platform")?
+        .map(|name| Platform::from_arg(&name))
+        .transpose()?
+        .unwrap_or(default_platform);
     match platform {
-        Platform::Codeforces => {
-            if rest.is_empty() {
-                print_usage();
-                process::exit(1);
-            }
-
-            let is_index_flag = |s: &str| s == "--index" || s == "-i";
-            let is_level_flag = |s: &str| s == "--level" || s == "-l";
-
-            if matches!(rest[0].as_str(), "dist" | "distribution") {
-                cf::run_distribution(&client)
-            } else if rest[0] == "stats" {
-                cf::run_stats(&client)
-            } else if rest.len() >= 2 && is_index_flag(&rest[0]) {
-                cf::run_index(&client, &rest[1])
-            } else if rest.len() >= 2 && is_index_flag(&rest[1]) {
-                cf::run_index(&client, &rest[0])
-            } else if rest.len() >= 2 && is_level_flag(&rest[0]) {
-                if rest.len() < 2 {
-                    println!("Error: Missing level after {}.", rest[0]);
-                    process::exit(1);
+        Platform::Codeforces => parse_codeforces(rest, json),
+        Platform::AtCoder => {
+            if rest.iter().any(|arg| arg == "--level" || arg == "-l") {
+                return Err("AtCoder has no levels; use --index or --pos.".to_string());
+            }
+            let index = match take_value(&mut rest, "--index")? {
+                Some(index) => Some(index),
+                None => take_value(&mut rest, "-i")?,
+            };
+            if let Some(index) = index {
+                rest.insert(0, index);
+            }
+            parse_atcoder(rest)
+        }
+    }
+}
+
+fn parse_codeforces(rest: Vec<String>, json: bool) -> Result<Command, String> {
+    let command = parse_codeforces_mode(rest, json)?;
+    if let Command::Codeforces { mode, options } = &command {
+        if options.gym && !matches!(mode, CfMode::Index(_)) {
+            return Err(
+                "Gym problems have no ratings, so --gym picks by index only, e.g. --gym --index C."
+                    .to_string(),
+            );
+        }
+    }
+    Ok(command)
+}
+
+fn parse_codeforces_mode(mut rest: Vec<String>, json: bool) -> Result<Command, String> {
+    if rest.first().is_some_and(|arg| arg == "me") {
+        return Ok(Command::Profile {
+            platform: Platform::Codeforces,
+            submissions: take_flag(&mut rest, "--submissions"),
+        });
+    }
+
+    let mut options = cf::PickOptions {
+        exclude_attempted: take_flag(&mut rest, "--exclude-attempted"),
+        review: take_flag(&mut rest, "--solved"),
+        retry: take_flag(&mut rest, "--attempted"),
+        strategy: take_strategy(&mut rest)?,
+        solved_verdicts: take_value(&mut rest, "--solved-verdicts")?
+            .map(|spec| cf::parse_verdicts(&spec))
+            .transpose()?
+            .unwrap_or_default(),
+        division: take_value(&mut rest, "--division")?
+            .map(|name| cf::Division::from_name(&name))
+            .transpose()?,
+        tier: take_flag(&mut rest, "--tier"),
+        listing: take_listing(&mut rest, json)?,
+        include_unfinished: take_flag(&mut rest, "--include-unfinished"),
+        tiebreak: take_value(&mut rest, "--tiebreak")?
+            .map(|name| cf::Tiebreak::from_name(&name))
+            .transpose()?
+            .unwrap_or_default(),
+        remaining: take_flag(&mut rest, "--remaining"),
+        format: take_format(&mut rest)?,
+        name_scheme: take_value(&mut rest, "--name-scheme")?
+            .map(|template| cf::NameScheme::parse(&template))
+            .transpose()?,
+        print_path: take_flag(&mut rest, "--print-path"),
+        dry_run: take_flag(&mut rest, "--dry-run"),
+        no_file: take_flag(&mut rest, "--no-file"),
+        index: None,
+        tags: take_value(&mut rest, "--tags")?
+            .map(|spec| cf::parse_tags(&spec))
+            .transpose()?
+            .unwrap_or_default(),
+        any_tag: take_flag(&mut rest, "--any-tag"),
+        exclude_tags: take_value(&mut rest, "--exclude-tags")?
+            .map(|spec| cf::parse_tags(&spec))
+            .transpose()?,
+        confirm: take_flag(&mut rest, "--confirm"),
+        contests: take_value(&mut rest, "--contests")?
+            .map(|spec| cf::ContestRange::parse(&spec))
+            .transpose()?
+            .unwrap_or_default(),
+        window: take_window(&mut rest)?,
+        gym: take_flag(&mut rest, "--gym"),
+        seed: take_seed(&mut rest)?,
+        skip_recent_days: take_value(&mut rest, "--skip-recent")?
+            .map(|value| match value.parse::<u64>() {
+                Ok(days) if days > 0 => Ok(days),
+                _ => Err("--skip-recent takes a positive number of days."),
+            })
+            .transpose()?,
+        spoiler_free: take_flag(&mut rest, "--spoiler-free"),
+        exclude_combined: take_flag(&mut rest, "--no-combined"),
+        also_unsolved_by: take_value(&mut rest, "--also-unsolved-by")?,
+        exclude_interactive: take_flag(&mut rest, "--no-interactive"),
+        solved_by: take_value(&mut rest, "--solved-by")?,
+        fallback: take_value(&mut rest, "--fallback")?
+            .map(|value| cf::parse_fallback(&value))
+            .transpose()?,
+        min_solved: take_value(&mut rest, "--min-solved")?
+            .map(|value| {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| "--min-solved must be a non-negative integer.")
+            })
+            .transpose()?,
+        limit: None,
+        // `contests --count` means something else
+        count: if rest.first().is_some_and(|arg| arg == "contests") {
+            None
+        } else {
+            take_value(&mut rest, "--count")?
+                .map(|value| match value.parse::<usize>() {
+                    Ok(count) if count > 0 => Ok(count),
+                    _ => Err("--count must be a positive integer."),
+                })
+                .transpose()?
+        },
+    };
+    if options.count.is_some()
+        && (options.confirm || options.listing.is_some() || options.remaining)
+    {
+        return Err(
+            "--count cannot be combined with --confirm, --list or --remaining.".to_string(),
+        );
+    }
+    if options.confirm && (options.listing.is_some() || options.remaining) {
+        return Err("--confirm cannot be combined with --list or --remaining.".to_string());
+    }
+    if options.retry && (options.review || options.exclude_attempted) {
+        return Err(
+            "--attempted cannot be combined with --solved or --exclude-attempted.".to_string(),
+        );
+    }
+    if options.any_tag && options.tags.is_empty() {
+        return Err("--any-tag needs --tags.".to_string());
+    }
+    if options.print_path && options.no_file {
+        return Err("--print-path cannot be combined with --no-file.".to_string());
+    }
+    if options.print_path && (options.listing.is_some() || options.remaining) {
+        return Err("--print-path cannot be combined with --list or --remaining.".to_string());
+    }
+    if json && (options.print_path || options.format.is_some()) {
+        return Err("--json cannot be combined with --print-path or --format.".to_string());
+    }
+
+    // `problem 1851 C` (or `problem 1851C`): one known problem, no selection
+    if rest.first().is_some_and(|arg| arg == "problem") {
+        let id = match &rest[1..] {
+            [id] => id.clone(),
+            [contest, index] => format!("{contest}{index}"),
+            _ => return Err("Usage: cf-lvl codeforces problem <contest> <index>".to_string()),
+        };
+        return match ProblemUrl::parse_id(&id)? {
+            ProblemUrl::Codeforces { contest_id, index } => Ok(Command::OpenCodeforces {
+                contest_id,
+                index,
+                options,
+            }),
+            ProblemUrl::AtCoder { .. } => Err(format!(
+                "'{id}' is not a Codeforces problem; use e.g. problem 1851 C."
+            )),
+        };
+    }
+
+    let is_index_flag = |s: &str| s == "--index" || s == "-i";
+    let is_level_flag = |s: &str| s == "--level" || s == "-l";
+
+    // `--level 14 --index D`: the index narrows the level pick
+    if rest.iter().any(|arg| is_level_flag(arg)) && rest.iter().any(|arg| is_index_flag(arg)) {
+        let level = match take_value(&mut rest, "--level")? {
+            Some(value) => value,
+            None => take_value(&mut rest, "-l")?.ok_or("Missing level after -l.")?,
+        };
+        let letter = match take_value(&mut rest, "--index")? {
+            Some(value) => value,
+            None => take_value(&mut rest, "-i")?.ok_or("Missing index after -i.")?,
+        };
+        reject_extra(&rest)?;
+        options.index = Some(cf::normalize_index(&letter).map_err(|err| err.to_string())?);
+        return Ok(Command::Codeforces {
+            mode: level_mode(&level)?,
+            options,
+        });
+    }
+
+    let retry = options.retry;
+    // daily is one fixed problem per date, so nothing may list, multiply or reseed it
+    let daily_conflict = if options.listing.is_some() || options.remaining {
+        Some("--list or --remaining")
+    } else if options.count.is_some() {
+        Some("--count")
+    } else if options.confirm {
+        Some("--confirm")
+    } else if options.strategy.is_some() || options.seed.is_some() {
+        Some("a strategy or --seed")
+    } else {
+        None
+    };
+    let command = |mode| Ok(Command::Codeforces { mode, options });
+
+    if take_flag(&mut rest, "--auto") {
+        reject_extra(&rest)?;
+        return command(CfMode::Auto);
+    }
+    if take_flag(&mut rest, "--first-unsolved") {
+        reject_extra(&rest)?;
+        return command(CfMode::FirstUnsolved);
+    }
+    let min_rating = take_value(&mut rest, "--min-rating")?;
+    let max_rating = take_value(&mut rest, "--max-rating")?;
+    let ratings = take_value(&mut rest, "--ratings")?;
+    if ratings.is_some() && (min_rating.is_some() || max_rating.is_some()) {
+        return Err("Use either --ratings or --min-rating/--max-rating.".to_string());
+    }
+    if let Some(spec) = ratings {
+        reject_extra(&rest)?;
+        return command(CfMode::Ratings(parse_ratings(&spec)?));
+    }
+    if min_rating.is_some() || max_rating.is_some() {
+        reject_extra(&rest)?;
+        return command(CfMode::Ratings(parse_rating_range(
+            min_rating.as_deref(),
+            max_rating.as_deref(),
+        )?));
+    }
+
+    if rest.is_empty() {
+        // Unfinished problems are rare enough to look at every rating
+        return command(if retry {
+            CfMode::Ratings(parse_rating_range(None, None)?)
+        } else {
+            CfMode::FromRating
+        });
+    }
+
+    let missing = |what: &str| format!("Missing {what} after '{}'.", rest[0]);
+
+    let mode = if matches!(rest[0].as_str(), "dist" | "distribution") {
+        let mut args = rest[1..].to_vec();
+        let view = cf::DistView {
+            by_index: take_flag(&mut args, "--by-index"),
+            progress: take_flag(&mut args, "--progress"),
+            width: take_value(&mut args, "--width")?
+                .map(|value| match value.parse::<usize>() {
+                    Ok(width) if width > 0 => Ok(width),
+                    _ => Err("--width must be a positive integer."),
+                })
+                .transpose()?,
+        };
+        reject_extra(&args)?;
+        if view.by_index && view.progress {
+            return Err("--progress cannot be combined with --by-index.".to_string());
+        }
+        if view.by_index && view.width.is_some() {
+            return Err("--width sets the bar length, and --by-index draws no bars.".to_string());
+        }
+        CfMode::Distribution(view)
+    } else if rest[0] == "stats" {
+        reject_extra(&rest[1..])?;
+        CfMode::Stats
+    } else if rest[0] == "virtual" {
+        let mut args = rest[1..].to_vec();
+        let timer = take_value(&mut args, "--timer")?
+            .map(|spec| parse_duration(&spec))
+            .transpose()?;
+        reject_extra(&args)?;
+        CfMode::Virtual { timer }
+    } else if rest[0] == "latest" {
+        reject_extra(&rest[1..])?;
+        CfMode::Latest
+    } else if rest[0] == "upsolve" {
+        reject_extra(&rest[1..])?;
+        CfMode::Upsolve
+    } else if rest[0] == "search" {
+        if let Some(flag) = rest[1..].iter().find(|arg| arg.starts_with('-')) {
+            return Err(format!("Unknown option '{flag}'. See cf-lvl --help."));
+        }
+        let query = rest[1..].join(" ");
+        if query.trim().is_empty() {
+            return Err(missing("part of a problem name"));
+        }
+        CfMode::Search(query)
+    } else if rest[0] == "pick" {
+        let level = rest.get(1).ok_or_else(|| missing("a level"))?;
+        reject_extra(&rest[2..])?;
+        level_mode(level)?
+    } else if rest[0] == "index" {
+        let letter = rest.get(1).ok_or_else(|| missing("an index letter"))?;
+        reject_extra(&rest[2..])?;
+        index_mode(letter)?
+    } else if rest[0] == "daily" {
+        if let Some(flags) = daily_conflict {
+            return Err(format!(
+                "daily picks one problem per date and cannot be combined with {flags}."
+            ));
+        }
+        let mut args = rest[1..].to_vec();
+        let level = match take_value(&mut args, "--level")? {
+            Some(value) => Some(value),
+            None => take_value(&mut args, "-l")?,
+        }
+        .map(|value| parse_level(&value))
+        .transpose()?;
+        let date = match take_value(&mut args, "--date")? {
+            Some(date) => {
+                if date.len() != 10 || parse_time_bound(&date).is_err() {
+                    return Err("--date must look like 2024-05-01.".to_string());
                 }
-                let level: u32 = rest[1].parse().unwrap_or_else(|_| {
-                    println!(
-                        "Error: Could not parse the provided level. Please provide a valid integer."
-                    );
-                    process::exit(1);
-                });
-                cf::run_level(&client, level)
-            } else if rest.len() >= 2 && is_level_flag(&rest[1]) {
-                let level: u32 = rest[0].parse().unwrap_or_else(|_| {
-                    println!(
-                        "Error: Could not parse the provided level. Please provide a valid integer."
-                    );
-                    process::exit(1);
-                });
-                cf::run_level(&client, level)
+                date
+            }
+            None => utils::today_utc(),
+        };
+        reject_extra(&args)?;
+        CfMode::Daily { level, date }
+    } else if rest[0] == "contests" {
+        let mut args = rest[1..].to_vec();
+        let completion = match (
+            take_flag(&mut args, "--complete"),
+            take_flag(&mut args, "--incomplete"),
+        ) {
+            (true, true) => return Err("Use only one of --complete and --incomplete.".to_string()),
+            (true, false) => cf::Completion::Complete,
+            (false, true) => cf::Completion::Incomplete,
+            (false, false) => cf::Completion::All,
+        };
+        let count = take_value(&mut args, "--count")?
+            .map(|value| {
+                value
+                    .parse::<usize>()
+                    .map_err(|_| "--count must be a non-negative integer.")
+            })
+            .transpose()?;
+        reject_extra(&args)?;
+        CfMode::Contests { completion, count }
+    } else if rest.len() >= 2 && is_index_flag(&rest[0]) {
+        reject_extra(&rest[2..])?;
+        index_mode(&rest[1])?
+    } else if rest.len() >= 2 && is_index_flag(&rest[1]) {
+        reject_extra(&rest[2..])?;
+        index_mode(&rest[0])?
+    } else if is_level_flag(&rest[0]) {
+        let value = rest
+            .get(1)
+            .ok_or_else(|| format!("Missing level after {}.", rest[0]))?;
+        reject_extra(&rest[2..])?;
+        level_mode(value)?
+    } else if rest[0].parse::<u32>().is_ok() || parse_relative(&rest[0]).is_some() {
+        // `15 --level`, plain `15` or `+2`: default to level mode
+        let consumed = if rest.get(1).is_some_and(|arg| is_level_flag(arg)) {
+            2
+        } else {
+            1
+        };
+        reject_extra(&rest[consumed..])?;
+        level_mode(&rest[0])?
+    } else if rest[0].starts_with('-') {
+        return Err(format!("Unknown option '{}'. See cf-lvl --help.", rest[0]));
+    } else {
+        return Err(format!(
+            "Unrecognized command '{}'. Give a level, a subcommand (pick, index, dist, stats, \
+             daily, contests, me) or a platform (codeforces, atcoder). See cf-lvl --help.",
+            rest[0]
+        ));
+    };
+    command(mode)
+}
+
+fn parse_atcoder(mut rest: Vec<String>) -> Result<Command, String> {
+    if rest.first().is_some_and(|arg| arg == "me") {
+        if rest.iter().any(|arg| arg == "--submissions") {
+            return Err("--submissions is only available for Codeforces.".to_string());
+        }
+        return Ok(Command::Profile {
+            platform: Platform::AtCoder,
+            submissions: false,
+        });
+    }
+
+    let options = atc::PickOptions {
+        window: take_window(&mut rest)?,
+        allow_partial: take_flag(&mut rest, "--allow-partial"),
+        format: take_format(&mut rest)?,
+        random: take_flag(&mut rest, "--random"),
+        dry_run: take_flag(&mut rest, "--dry-run"),
+        no_file: take_flag(&mut rest, "--no-file"),
+        confirm: take_flag(&mut rest, "--confirm"),
+        seed: take_seed(&mut rest)?,
+    };
+
+    if let Some(value) = take_value(&mut rest, "--pos")? {
+        let position: u32 = value
+            .parse()
+            .map_err(|_| "--pos must be a positive integer.")?;
+        reject_extra(&rest)?;
+        return Ok(Command::AtCoder {
+            index: atc::position_to_index(position)?,
+            position: Some(position),
+            options,
+        });
+    }
+
+    reject_extra(rest.get(1..).unwrap_or_default())?;
+    match rest.into_iter().next() {
+        Some(index) => Ok(Command::AtCoder {
+            index,
+            position: None,
+            options,
+        }),
+        None => Ok(Command::Usage),
+    }
+}
+
+fn execute(command: Command) -> Result<(), Box<dyn Error>> {
+    match command {
+        Command::Help => print_usage(),
+        Command::HelpTopic(text) => println!("{text}"),
+        Command::Version => println!(
+            "cf-lvl {} ({} {})",
+            env!("CARGO_PKG_VERSION"),
+            env!("CF_LVL_GIT_COMMIT"),
+            env!("CF_LVL_BUILD_DATE")
+        ),
+        Command::Usage => {
+            // Not asked for, so it is an error report, not output
+            eprintln!("{}", usage_text());
+            process::exit(error::EXIT_USAGE);
+        }
+        Command::Config(ConfigAction::List) => config::run_list()?,
+        Command::Config(ConfigAction::Get(key)) => config::run_get(&key)?,
+        Command::Config(ConfigAction::Set(key, value)) => config::run_set(&key, &value)?,
+        Command::Skip(SkipAction::List) => skips::run_list()?,
+        Command::Skip(SkipAction::Add(id)) => skips::run_add(&id)?,
+        Command::Skip(SkipAction::Remove(id)) => skips::run_remove(&id)?,
+        Command::Config(ConfigAction::Check) => {
+            if !config::run_check() {
+                process::exit(error::EXIT_CONFIG);
+            }
+        }
+        Command::Init => config::run_init(&build_client()?)?,
+        Command::Doctor => {
+            if !config::run_doctor(&build_client()?) {
+                process::exit(error::EXIT_CONFIG);
+            }
+        }
+        Command::Completions(shell) => print!("{}", completions::script(shell)),
+        Command::Man => print!("{}", man::page(usage_text())),
+        Command::Explain(command) => explain(&command),
+        Command::OpenCodeforces {
+            contest_id,
+            index,
+            options,
+        } => {
+            let client = build_client()?;
+            cf::run_problem(&client, contest_id, &index, &options)?;
+        }
+        Command::OpenAtCoder {
+            contest_id,
+            task_id,
+            options,
+        } => {
+            let client = build_client()?;
+            atc::run_task(&client, &contest_id, &task_id, &options)?;
+        }
+        Command::Profile {
+            platform,
+            submissions,
+        } => {
+            let settings = config::settings()?;
+            match platform {
+                Platform::Codeforces => cf::run_me(settings.codeforces_handle()?, submissions)?,
+                Platform::AtCoder => atc::run_me(settings.atcoder_handle()?)?,
+            }
+        }
+        Command::Codeforces { mode, options } => {
+            let client = build_client()?;
+            match mode {
+                CfMode::Level(level) => cf::run_level(&client, level, &options)?,
+                CfMode::Relative(offset) => cf::run_relative(&client, offset, &options)?,
+                CfMode::FromRating => cf::run_from_rating(&client, &options)?,
+                CfMode::Index(index) => cf::run_index(&client, &index, &options)?,
+                CfMode::Ratings(ratings) => cf::run_ratings(&client, &ratings, &options)?,
+                CfMode::Auto => cf::run_auto(&client, &options)?,
+                CfMode::FirstUnsolved => cf::run_first_unsolved(&client, &options)?,
+                CfMode::Daily { level, date } => cf::run_daily(&client, level, &date, &options)?,
+                CfMode::Distribution(view) => cf::run_distribution(&client, view, &options)?,
+                CfMode::Stats => cf::run_stats(&client, &options)?,
+                CfMode::Search(query) => cf::run_search(&client, &query, &options)?,
+                CfMode::Upsolve => cf::run_upsolve(&client, &options)?,
+                CfMode::Latest => cf::run_latest(&client, &options)?,
+                CfMode::Virtual { timer } => cf::run_virtual(&client, timer, &options)?,
+                CfMode::Contests { completion, count } => {
+                    cf::run_contests(&client, completion, count, &options)?
+                }
+            }
+        }
+        Command::AtCoder {
+            index,
+            position,
+            options,
+        } => {
+            if let Some(position) = position {
+                let line = format!("Position {position} resolves to task '{index}'.");
+                if utils::is_json() {
+                    eprintln!("{line}");
+                } else if !utils::is_quiet() {
+                    println!("{line}");
+                }
+            }
+            let client = build_client()?;
+            atc::run(&client, &index, &options)?;
+        }
+    }
+    Ok(())
+}
+
+/// Prints what `command` would do, for `cf-lvl explain`.
+fn explain(command: &Command) {
+    let (platform, mode, runs) = match command {
+        Command::Help | Command::Usage => ("-", "print usage".to_string(), "print_usage"),
+        Command::HelpTopic(_) => ("-", "print a help topic".to_string(), "help::topic"),
+        Command::Version => ("-", "print version".to_string(), "main"),
+        Command::Config(action) => match action {
+            ConfigAction::Check => ("-", "validate settings".to_string(), "config::run_check"),
+            ConfigAction::List => ("-", "list settings".to_string(), "config::run_list"),
+            ConfigAction::Get(key) => ("-", format!("print {key}"), "config::run_get"),
+            ConfigAction::Set(key, value) => {
+                ("-", format!("set {key} to '{value}'"), "config::run_set")
+            }
+        },
+        Command::Skip(action) => match action {
+            SkipAction::List => (
+                "Codeforces",
+                "list skipped problems".to_string(),
+                "skips::run_list",
+            ),
+            SkipAction::Add(id) => (
+                "Codeforces",
+                format!("skip {id} from now on"),
+                "skips::run_add",
+            ),
+            SkipAction::Remove(id) => (
+                "Codeforces",
+                format!("allow {id} again"),
+                "skips::run_remove",
+            ),
+        },
+        Command::Init => ("-", "interactive setup".to_string(), "config::run_init"),
+        Command::Doctor => ("-", "diagnose setup".to_string(), "config::run_doctor"),
+        Command::Completions(shell) => (
+            "-",
+            format!("{shell:?} completion script"),
+            "completions::script",
+        ),
+        Command::Man => ("-", "manual page".to_string(), "man::page"),
+        Command::Explain(_) => ("-", "explain".to_string(), "explain"),
+        Command::OpenCodeforces {
+            contest_id, index, ..
+        } => (
+            "Codeforces",
+            format!("open problem {contest_id}{index}"),
+            "codeforces::run_problem",
+        ),
+        Command::OpenAtCoder {
+            contest_id,
+            task_id,
+            ..
+        } => (
+            "AtCoder",
+            format!("open task {task_id} of {contest_id}"),
+            "atcoder::run_task",
+        ),
+        Command::Profile {
+            platform: Platform::Codeforces,
+            submissions,
+        } => (
+            "Codeforces",
+            if *submissions {
+                "open your submissions"
             } else {
-                // Default to level mode
-                let level: u32 = rest[0].parse().unwrap_or_else(|_| {
-                    println!(
-                        "Error: Could not parse the provided level. Please provide a valid integer."
-                    );
-                    process::exit(1);
-                });
-                cf::run_level(&client, level)
+                "open your profile"
             }
+            .to_string(),
+            "codeforces::run_me",
+        ),
+        Command::Profile {
+            platform: Platform::AtCoder,
+            ..
+        } => (
+            "AtCoder",
+            "open your profile".to_string(),
+            "atcoder::run_me",
+        ),
+        Command::Codeforces { mode, .. } => {
+            let (mode, runs) = match mode {
+                CfMode::Level(level) => (
+                    format!("level {level} (rating {})", level * 100),
+                    "codeforces::run_level",
+                ),
+                CfMode::Relative(offset) => (
+                    format!("level {offset:+} from your rating (rounded to 100)"),
+                    "codeforces::run_relative",
+                ),
+                CfMode::FromRating => (
+                    match config::settings()
+                        .ok()
+                        .and_then(|settings| cf::parse_level_offset(&settings.level_offset.value).ok())
+                    {
+                        Some(offset) => format!(
+                            "level {offset:+} from your rating (rounded to 100, codeforces.level_offset)"
+                        ),
+                        None => "your rating plus codeforces.level_offset".to_string(),
+                    },
+                    "codeforces::run_from_rating",
+                ),
+                CfMode::Index(index) => (format!("index {index}"), "codeforces::run_index"),
+                CfMode::Ratings(ratings) => {
+                    let list: Vec<String> = ratings.iter().map(|r| r.to_string()).collect();
+                    (
+                        format!("ratings {}", list.join(", ")),
+                        "codeforces::run_ratings",
+                    )
+                }
+                CfMode::Auto => (
+                    "rating band from your current rating".to_string(),
+                    "codeforces::run_auto",
+                ),
+                CfMode::FirstUnsolved => (
+                    "lowest rating with a candidate".to_string(),
+                    "codeforces::run_first_unsolved",
+                ),
+                CfMode::Daily { level, date } => {
+                    let mut mode = format!("daily pick for {date}");
+                    if let Some(level) = level {
+                        mode.push_str(&format!(", rating {}", level * 100));
+                    }
+                    (mode, "codeforces::run_daily")
+                }
+                CfMode::Distribution(view) => (
+                    if view.by_index {
+                        "rating distribution per index A-F".to_string()
+                    } else if view.progress {
+                        "rating distribution with your solved share".to_string()
+                    } else {
+                        "rating distribution".to_string()
+                    },
+                    "codeforces::run_distribution",
+                ),
+                CfMode::Stats => ("solved stats".to_string(), "codeforces::run_stats"),
+                CfMode::Virtual { timer } => (
+                    match timer {
+                        Some(timer) => format!(
+                            "virtual set A-F from untouched contests, {} min timer",
+                            timer.as_secs() / 60
+                        ),
+                        None => "virtual set A-F from untouched contests".to_string(),
+                    },
+                    "codeforces::run_virtual",
+                ),
+                CfMode::Latest => (
+                    "every problem of the newest finished contest".to_string(),
+                    "codeforces::run_latest",
+                ),
+                CfMode::Upsolve => (
+                    "first unsolved problem of your newest live round".to_string(),
+                    "codeforces::run_upsolve",
+                ),
+                CfMode::Search(query) => (
+                    format!("problems named like '{query}', then ask which to open"),
+                    "codeforces::run_search",
+                ),
+                CfMode::Contests { completion, count } => {
+                    let mut mode = format!("contest progress ({completion:?})");
+                    if let Some(count) = count {
+                        mode.push_str(&format!(", newest {count}"));
+                    }
+                    (mode, "codeforces::run_contests")
+                }
+            };
+            ("Codeforces", mode, runs)
         }
-        Platform::AtCoder => {
-            if rest.is_empty() {
-                print_usage();
-                process::exit(1);
+        Command::AtCoder {
+            index, position, ..
+        } => {
+            let mode = match position {
+                Some(position) => format!("ABC task {index} (position {position})"),
+                None => format!("ABC task {index}"),
+            };
+            ("AtCoder", mode, "atcoder::run")
+        }
+    };
+
+    let mut rows: Vec<(&str, String)> = vec![("Platform", platform.to_string()), ("Mode", mode)];
+    match command {
+        Command::Codeforces { options, .. } => rows.extend(describe_options(options)),
+        Command::AtCoder { options, .. } => {
+            let bound = |b: Option<u64>| b.map_or("-".to_string(), |b| b.to_string());
+            rows.push((
+                "Window",
+                format!(
+                    "since {} before {}",
+                    bound(options.window.since),
+                    bound(options.window.before)
+                ),
+            ));
+            rows.push((
+                "Strategy",
+                match options.seed {
+                    Some(seed) if options.random => format!("random, seed {seed}"),
+                    _ => if options.random { "random" } else { "newest" }.to_string(),
+                },
+            ));
+            if options.allow_partial {
+                rows.push((
+                    "Partial",
+                    "keep earlier submission pages if a later one fails".to_string(),
+                ));
             }
-            atc::run(&client, &rest[0])
         }
+        _ => {}
+    }
+    rows.push(("Runs", runs.to_string()));
+    // One column wider than the longest label, so values never touch it
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0) + 2;
+    for (label, value) in rows {
+        println!("{:<width$}{value}", format!("{label}:"));
+    }
+}
+
+/// Pairs each Codeforces filter with its effective value, defaults included.
+fn describe_options(options: &cf::PickOptions) -> Vec<(&'static str, String)> {
+    let mut division = match (options.division, options.tier) {
+        _ if options.gym => "gym (the 10 newest contests)".to_string(),
+        (Some(division), _) => format!("{division:?}"),
+        (None, true) => "by rating (--tier)".to_string(),
+        (None, false) => "Div2".to_string(),
+    };
+    let takes_div2 = !options.gym
+        && match options.division {
+            Some(division) => division == cf::Division::Div2,
+            None => !options.tier,
+        };
+    if takes_div2 {
+        let combined = !options.exclude_combined
+            && config::settings()
+                .map_or(true, |settings| settings.include_combined.value != "false");
+        division.push_str(if combined {
+            ", with A-D of combined rounds"
+        } else {
+            ", without combined rounds"
+        });
+    }
+    let mut strategy = options
+        .strategy
+        .map_or("mode default".to_string(), |s| s.name().to_string());
+    if let Some(seed) = options.seed {
+        strategy.push_str(&format!(", seed {seed}"));
+    }
+    let verdicts = if options.solved_verdicts.is_empty() {
+        "OK".to_string()
+    } else {
+        options.solved_verdicts.join(",")
+    };
+    let pick = match options.listing {
+        Some(listing) if options.remaining => format!("count and list ({listing:?})"),
+        None if options.remaining => "count".to_string(),
+        Some(listing) => match options.limit {
+            Some(limit) => format!("list ({listing:?}), newest {limit}"),
+            None => format!("list ({listing:?})"),
+        },
+        None if options.count.is_some_and(|count| count > 1) => {
+            format!("batch of {}", options.count.unwrap_or_default())
+        }
+        None if options.dry_run => "dry run".to_string(),
+        None if options.confirm => "ask, then open".to_string(),
+        None if options.print_path => "print path".to_string(),
+        None if utils::is_json() => "print JSON".to_string(),
+        None => "open".to_string(),
+    };
+    let mut filters = Vec::new();
+    if let Some(letter) = options.index {
+        filters.push(format!("index {letter}"));
+    }
+    if !options.tags.is_empty() {
+        let quantifier = if options.any_tag { "any of" } else { "all of" };
+        filters.push(format!("tags {quantifier} {}", options.tags.join(", ")));
+    }
+    if let Some(range) = options.contests.describe() {
+        filters.push(format!("contests {range}"));
+    }
+    if let Some(window) = options.window.describe() {
+        filters.push(window);
+    }
+    if let Some(min) = options.min_solved {
+        filters.push(format!("solved by at least {min}"));
+    }
+    if options.exclude_interactive {
+        filters.push("not interactive".to_string());
+    }
+    if let Some(rival) = &options.also_unsolved_by {
+        filters.push(format!("not solved by {rival} either"));
+    }
+    if let Some(rival) = &options.solved_by {
+        filters.push(format!("solved by {rival}"));
+    }
+    match options.fallback {
+        Some(0) => {}
+        Some(width) => filters.push(format!("level falls back up to {width} away")),
+        None => {
+            if let Ok(settings) = config::settings() {
+                let configured = settings.fallback.value.trim();
+                if configured != "0" {
+                    filters.push(format!("level falls back up to {configured} away (config)"));
+                }
+            }
+        }
+    }
+    match &options.exclude_tags {
+        Some(tags) => filters.push(format!("without tags {}", tags.join(", "))),
+        None => {
+            if let Ok(settings) = config::settings() {
+                let configured = settings.exclude_tags.value.trim();
+                if !configured.is_empty() {
+                    filters.push(format!("without tags {configured} (config)"));
+                }
+            }
+        }
+    }
+    vec![
+        ("Division", division),
+        (
+            "Filters",
+            if filters.is_empty() {
+                "none".to_string()
+            } else {
+                filters.join("; ")
+            },
+        ),
+        ("Strategy", strategy),
+        ("Tiebreak", format!("{:?}", options.tiebreak)),
+        ("Solved", verdicts),
+        ("Pool", options.pool_label().to_string()),
+        (
+            "Attempted",
+            match (options.exclude_attempted, options.skip_recent_days) {
+                (true, _) => "excluded".to_string(),
+                (false, Some(days)) => format!("allowed unless sent in the last {days} day(s)"),
+                (false, None) => "allowed".to_string(),
+            },
+        ),
+        (
+            "Contests",
+            if options.include_unfinished {
+                "all"
+            } else {
+                "finished"
+            }
+            .to_string(),
+        ),
+        ("Output", pick),
+    ]
+}
+
+/// The `help` page for the subcommand in `words`, such as `dist` in
+/// `cf dist --width 40`, or the full usage when it has no page of its own.
+fn subcommand_help(words: &[String]) -> Command {
+    let mut words = words
+        .iter()
+        .map(String::as_str)
+        .filter(|word| !word.starts_with('-'));
+    let Some(first) = words.next() else {
+        return Command::Help;
+    };
+    let name = match Platform::from_arg(first) {
+        Ok(Platform::AtCoder) => "atcoder",
+        Ok(Platform::Codeforces) => match words.next() {
+            Some(word) => word,
+            None => return Command::Help,
+        },
+        Err(_) => first,
+    };
+    // A bare level such as `14 --help` is level mode
+    let name = if name.trim_start_matches(['+', '-']).parse::<u32>().is_ok() {
+        "level"
+    } else {
+        name
+    };
+    help::topic(name).map_or(Command::Help, Command::HelpTopic)
+}
+
+/// Rejects whatever a parser left behind, so typos are not silently ignored.
+fn reject_extra(args: &[String]) -> Result<(), String> {
+    match args.first() {
+        None => Ok(()),
+        Some(arg) if arg.starts_with('-') => {
+            Err(format!("Unknown option '{arg}'. See cf-lvl --help."))
+        }
+        Some(arg) => Err(format!("Unexpected argument '{arg}'. See cf-lvl --help.")),
     }
 }
 
+/// Splits bundled short flags such as `-qv` into `-q -v`.
+fn expand_short_flags(args: Vec<String>) -> Vec<String> {
+    const BUNDLEABLE: &str = "qv";
+    args.into_iter()
+        .flat_map(|arg| {
+            let bundle = arg
+                .strip_prefix('-')
+                .filter(|rest| rest.len() > 1 && rest.chars().all(|c| BUNDLEABLE.contains(c)));
+            match bundle {
+                Some(rest) => rest.chars().map(|c| format!("-{c}")).collect(),
+                None => vec![arg],
+            }
+        })
+        .collect()
+}
+
+/// Removes every occurrence of `flag` from `args`, returning whether it was given.
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != before
+}
+
+/// Removes the strategy flags from `args`, rejecting conflicting combinations.
+fn take_strategy(args: &mut Vec<String>) -> Result<Option<cf::Strategy>, String> {
+    let flags = [
+        ("--newest", cf::Strategy::Newest),
+        ("--oldest", cf::Strategy::Oldest),
+        ("--random", cf::Strategy::Random),
+        ("--variety", cf::Strategy::Variety),
+        ("--popular", cf::Strategy::Popular),
+    ];
+    let given: Vec<cf::Strategy> = flags
+        .into_iter()
+        .filter(|(flag, _)| take_flag(args, flag))
+        .map(|(_, strategy)| strategy)
+        .collect();
+    if given.len() > 1 {
+        return Err(
+            "Use only one of --newest, --oldest, --random, --variety and --popular.".to_string(),
+        );
+    }
+    Ok(given.first().copied())
+}
+
+/// Removes `--list` and its format flags; `--json`/`--jsonl` imply `--list`.
+fn take_listing(args: &mut Vec<String>, json: bool) -> Result<Option<cf::Listing>, String> {
+    let list = take_flag(args, "--list");
+    // The global --json turns a listing into one JSON array
+    match (list && json, take_flag(args, "--jsonl")) {
+        (true, true) => Err("Use only one of --json and --jsonl.".to_string()),
+        (true, false) => Ok(Some(cf::Listing::Json)),
+        (false, true) => Ok(Some(cf::Listing::JsonLines)),
+        (false, false) => Ok(list.then_some(cf::Listing::Text)),
+    }
+}
+
+/// Removes `flag <value>` from `args` and returns the value, if the flag was given.
+fn take_value(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    let Some(pos) = args.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    if pos + 1 >= args.len() {
+        return Err(format!("Missing value after {flag}."));
+    }
+    let value = args.remove(pos + 1);
+    args.remove(pos);
+    Ok(Some(value))
+}
+
+fn take_format(args: &mut Vec<String>) -> Result<Option<String>, String> {
+    take_value(args, "--format")?
+        .map(|template| utils::parse_format(&template))
+        .transpose()
+}
+
+fn take_seed(args: &mut Vec<String>) -> Result<Option<u64>, String> {
+    take_value(args, "--seed")?
+        .map(|value| {
+            value
+                .parse::<u64>()
+                .map_err(|_| "--seed must be a non-negative integer.".to_string())
+        })
+        .transpose()
+}
+
+/// Removes `--since` / `--before`, which each take a time bound.
+fn take_window(args: &mut Vec<String>) -> Result<ContestWindow, String> {
+    let window = ContestWindow {
+        since: take_time_bound(args, "--since")?,
+        before: take_time_bound(args, "--before")?,
+    };
+    if let (Some(since), Some(before)) = (window.since, window.before) {
+        if since >= before {
+            return Err("--since must be earlier than --before.".to_string());
+        }
+    }
+    Ok(window)
+}
+
+fn take_time_bound(args: &mut Vec<String>, flag: &str) -> Result<Option<u64>, String> {
+    take_value(args, flag)?
+        .map(|spec| parse_time_bound(&spec))
+        .transpose()
+}
+
+fn parse_level(value: &str) -> Result<u32, String> {
+    let level: u32 = value.parse().map_err(|_| {
+        "Could not parse the provided level. Please provide a valid integer.".to_string()
+    })?;
+    if !(8..=32).contains(&level) {
+        return Err("Level must be an integer between 8 and 32 inclusive.".to_string());
+    }
+    Ok(level)
+}
+
+/// Reads `+N`/`-N` as a relative level; anything else is `None`.
+fn parse_relative(value: &str) -> Option<i32> {
+    let digits = value.strip_prefix(['+', '-'])?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+/// An index argument, checked here so a typo is a usage error.
+fn index_mode(letter: &str) -> Result<CfMode, String> {
+    cf::normalize_index(letter).map_err(|err| err.to_string())?;
+    Ok(CfMode::Index(letter.to_string()))
+}
+
+/// A level argument: absolute like `14` or relative like `+2`.
+fn level_mode(value: &str) -> Result<CfMode, String> {
+    match parse_relative(value) {
+        Some(offset) => Ok(CfMode::Relative(offset)),
+        None => Ok(CfMode::Level(parse_level(value)?)),
+    }
+}
+
+fn parse_ratings(spec: &str) -> Result<BTreeSet<u32>, String> {
+    let ratings = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<u32>().map_err(|_| {
+                format!("Could not parse rating '{part}'. Use e.g. --ratings 1300,1500.")
+            })
+        })
+        .collect::<Result<BTreeSet<u32>, String>>()?;
+    if ratings.is_empty() {
+        return Err("Provide at least one rating.".to_string());
+    }
+    if let Some(bad) = ratings.iter().find(|&&r| !is_rating(r)) {
+        return Err(format!(
+            "Rating {bad} is invalid. Ratings must be multiples of 100 between 800 and 3200."
+        ));
+    }
+    Ok(ratings)
+}
+
+/// Whether a problem can have `rating`: a multiple of 100 within the rating bounds.
+fn is_rating(rating: u32) -> bool {
+    rating.is_multiple_of(100) && (cf::MIN_RATING..=cf::MAX_RATING).contains(&rating)
+}
+
+/// Every rating from `min` to `max` (800 and 3200 when left out), in steps of 100.
+fn parse_rating_range(min: Option<&str>, max: Option<&str>) -> Result<BTreeSet<u32>, String> {
+    let bound = |value: Option<&str>, flag: &str, fallback: u32| match value {
+        None => Ok(fallback),
+        Some(value) => match value.trim().parse::<u32>() {
+            Ok(rating) if is_rating(rating) => Ok(rating),
+            _ => Err(format!(
+                "{flag} must be a multiple of 100 between 800 and 3200, not '{value}'."
+            )),
+        },
+    };
+    let min = bound(min, "--min-rating", cf::MIN_RATING)?;
+    let max = bound(max, "--max-rating", cf::MAX_RATING)?;
+    if min > max {
+        return Err(format!("--min-rating {min} is above --max-rating {max}."));
+    }
+    Ok((min..=max).step_by(100).collect())
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("Error: {message}");
+    process::exit(error::EXIT_USAGE);
+}
+
 fn print_usage() {
-    println!(
-        "Problem Picker\n\
+    println!("{}", usage_text());
+}
+
+fn usage_text() -> &'static str {
+    "Problem Picker\n\
         Usage:\n\
+          cf-lvl                             # Codeforces Div. 2 at your rating + codeforces.level_offset\n\
           cf-lvl [level]                     # Codeforces Div. 2 by level (x100), default platform\n\
+          cf-lvl pick [--platform cf|ac] (--level N | --index X) [--random] ...\n\
+                                             # Pick on either platform with the same flags\n\
           cf-lvl --index [letter]            # Codeforces Div. 2 by index (A, B, C, ...)\n\
+          cf-lvl index [letter]              # Same as cf-lvl --index [letter]\n\
+          cf-lvl --level N --index X         # Codeforces Div. 2 at level N with index X\n\
+          cf-lvl --ratings 1300,1500         # Codeforces Div. 2 at any of the listed ratings\n\
+          cf-lvl --min-rating R --max-rating R\n\
+                                             # Codeforces Div. 2 at any rating in the range\n\
+          cf-lvl +2 | -1                     # Codeforces Div. 2 N levels above/below your rating\n\
+          cf-lvl --auto                      # Codeforces Div. 2 at or just above your current rating\n\
+          cf-lvl --first-unsolved            # Codeforces Div. 2 at the lowest rating with one left\n\
           cf-lvl dist                        # Rating distribution of Codeforces Div. 2 problems\n\
+          cf-lvl dist --by-index             # The same, one column per index A-F\n\
+          cf-lvl dist --progress             # The same, as solved/total (percent) per rating\n\
+          cf-lvl dist --width 60             # Scale the bars to 60 columns (default $COLUMNS, else 80)\n\
           cf-lvl stats                       # Rating stats of solved Codeforces Div. 2 problems\n\
+          cf-lvl daily [--level N]           # Same unsolved problem all day (UTC), optionally by level\n\
+          cf-lvl contests [--complete|--incomplete] [--count N]\n\
+                                             # Solved/total per Codeforces Div. 2 contest, newest first\n\
+          cf-lvl me [--submissions]          # Open your Codeforces profile or submissions page\n\
           cf-lvl atcoder [index]             # AtCoder ABC (explicit platform)\n\
+          cf-lvl atcoder me                  # Open your AtCoder profile\n\
+          cf-lvl atcoder [index] --since 1y  # AtCoder ABC from contests in a time window\n\
+          cf-lvl atcoder --pos [n]           # AtCoder ABC by task position (5 = e)\n\
+          cf-lvl atcoder [index] --random    # AtCoder ABC, a random unsolved task instead of the newest\n\
+          cf-lvl list [level] [--count N]    # Table of the newest N candidates (default 10); opens nothing\n\
+          cf-lvl open [1851C|abc250_d]       # Open one known problem (id or URL), skipping selection\n\
+          cf-lvl cf problem 1851 C           # The same for Codeforces, with the usual pick flags\n\
+          cf-lvl cf search two arrays        # Codeforces problems by name, with your status; pick one\n\
+          cf-lvl cf upsolve                  # First unsolved problem of the last round you took part in\n\
+          cf-lvl cf latest                   # Problems of the newest Div. 2 round; offers to stub the rest\n\
+          cf-lvl cf virtual [--timer 2h]     # One unsolved A-F from contests you never touched, stubbed\n\
           cf-lvl codeforces ...              # Optional explicit Codeforces platform prefix\n\
+          cf-lvl init                        # Answer a few questions and write the config file\n\
+          cf-lvl config check                # Validate and print the effective settings\n\
+          cf-lvl config list                 # Print every effective setting and its source\n\
+          cf-lvl config get|set [key] [val]  # Read or write one config.toml key\n\
+          cf-lvl doctor                      # Check settings, network and handles, with fixes\n\
+          cf-lvl completions [shell]         # Print a bash, zsh, fish or powershell completion script\n\
+          cf-lvl explain [args...]           # Show what the arguments would do, without running it\n\
+          cf-lvl help [topic]                # Flags, defaults and examples for one mode, e.g. help level\n\
+          cf-lvl <command> --help            # The same page for that command, e.g. dist --help\n\
+          cf-lvl man                         # Print this reference as a roff manual page\n\
+          cf-lvl [problem url]               # Open a pasted codeforces.com or atcoder.jp problem\n\
+          cf-lvl skip [id] | unskip <id>     # Never pick a Codeforces problem again, or undo; skip alone lists\n\
+        Options:\n\
+          --exclude-attempted                # Codeforces: also skip problems with rejected submissions\n\
+          --solved                           # Codeforces: review pick among problems already solved\n\
+          --attempted                        # Codeforces: pick among problems you tried but never solved\n\
+          --skip-recent N                    # Codeforces: skip problems you submitted to in the last N days\n\
+          --newest | --random                # Codeforces: pick the newest (default) or a random match\n\
+          --oldest                           # Codeforces: pick from the oldest contest instead\n\
+          --variety                          # Codeforces: prefer contests you've been shown least\n\
+          --popular                          # Codeforces: prefer the most solved problem\n\
+          --min-solved N                     # Codeforces: only problems at least N people solved\n\
+          --fallback 100|200                 # Codeforces: try the nearest ratings when a level is used up\n\
+          --spoiler-free                     # Codeforces: don't print the pick's tags\n\
+          --no-interactive                   # Codeforces: leave out interactive problems\n\
+          --also-unsolved-by HANDLE          # Codeforces: skip what this handle solved, too\n\
+          --solved-by HANDLE                 # Codeforces: only problems this handle solved\n\
+          --seed N                           # Repeatable --random and --tiebreak random picks\n\
+          --solved-verdicts OK,PARTIAL       # Codeforces: verdicts that count as solved (default OK)\n\
+          --division 1|2|3|4|edu|global      # Codeforces: contest division to pick from (default 2);\n\
+                                             # also combined, and 2 includes Educational rounds\n\
+          --tier                             # Codeforces: choose the division from each problem's rating\n\
+          --no-combined                      # Codeforces: Div. 2 picks without A-D of Div. 1 + Div. 2 rounds\n\
+          --contests 1700.. | ..1000         # Codeforces: only contests with ids in this range (inclusive)\n\
+          --since 2021 | --before 2019       # Only contests that started in this window\n\
+          --gym                              # Codeforces: pick by index from the 10 newest gym contests\n\
+          --list [--json|--jsonl]            # Codeforces: print all candidates (newest first) instead\n\
+          --json                             # Print the result as JSON (id, name, rating, url, path);\n\
+                                             # picks then create the file but open nothing\n\
+          --include-unfinished               # Codeforces: also use contests that haven't finished\n\
+          --tiebreak [index|rating|random]   # Codeforces: choose within one contest (default index)\n\
+          --remaining [--list]               # Codeforces: count the candidates left instead of picking\n\
+          --name-scheme [template]           # Codeforces: starter file name, e.g. {contest}{index}\n\
+          --print-path                       # Codeforces: print only the starter file path; open nothing\n\
+          --format [template]                # Print the pick as e.g. '{rating}\\t{name}\\t{url}'\n\
+          --tags [a,b]                       # Codeforces: only problems with all these tags\n\
+          --any-tag                          # Codeforces: with --tags, any one of them is enough\n\
+          --exclude-tags [a,b]               # Codeforces: never problems with any of these tags\n\
+                                             # (replaces codeforces.exclude_tags)\n\
+          --count [n]                        # Codeforces: pick n problems at once, creating each file\n\
+                                             # and printing each URL; nothing is opened\n\
+          --confirm                          # Propose picks one by one; y opens, n shows the next, q stops\n\
+          --dry-run                          # Show the pick and candidate count; write and open nothing\n\
+          --platform [cf|ac]                 # pick: platform to pick from (default default_platform)\n\
+          --allow-partial                    # AtCoder: tolerate a failed submissions page after the first\n\
+          --portable                         # Keep config, cache and history next to the executable\n\
+          --profile [name]                   # Use the [profile.<name>] table from the config file\n\
+          --handle [user]                    # Pick for this account on whichever platform runs\n\
+          --codeforces-handle [user]         # Use this Codeforces account instead of the configured one\n\
+          --atcoder-handle [user]            # Use this AtCoder account instead of the configured one\n\
+          --editor [command]                 # Open starter files with this command, e.g. 'code -g {path}'\n\
+          --browser [command]                # Open URLs with this command, e.g. 'open -a Firefox'\n\
+          --no-browser                       # Print URLs instead of opening them\n\
+          --no-file                          # Don't create a starter file (or open the editor)\n\
+          -V, --version                      # Print the version, git commit and build date\n\
+          -q, --quiet                        # Print only the starter file path (or URL) of a pick; no\n\
+                                             # progress, warnings or notes. Exit status 1 when nothing\n\
+                                             # matched (-qv combines short flags)\n\
+          -v, --verbose                      # Explain how the pick was made\n\
+          --max-requests [n]                 # Fail instead of making more than n HTTP requests\n\
+          --timings                          # Print how long each fetch took to stderr\n\
+          --refresh                          # Ignore cached problemset/contest data\n\
+          --max-age [30m|2h|1d]              # Refetch cached data older than this (default 6h)\n\
         Notes:\n\
+          - Settings live in config.toml in the platform config directory (~/.config/cf-lvl,\n\
+            ~/Library/Application Support/cf-lvl or %APPDATA%\\cf-lvl\\config):\n\
+            [codeforces] handle, dir; [atcoder] handle, dir; [contests] include, exclude;\n\
+            [editor] command; [browser] open, command;\n\
+            [strategy] level, index; and default_platform at the top level.\n\
+            Handles are required; codeforces.dir defaults to the current directory, and AtCoder\n\
+            starter files are only written when atcoder.dir is set.\n\
+          - codeforces.alt_handles = \"old,older\" adds other accounts: anything they solved\n\
+            counts as solved, while rating, attempts and rounds come from codeforces.handle alone.\n\
+          - Every key has an environment override above the config file: CF_LVL_ plus the key in\n\
+            capitals with dots as underscores, e.g. CF_LVL_CODEFORCES_EXCLUDE_TAGS. The shorter\n\
+            CF_LVL_HANDLE, CF_LVL_DIR, CF_LVL_EDITOR and CF_LVL_BROWSER still work; config check\n\
+            lists every name, and CF_LVL_CONFIG points at a different config file.\n\
+          - Portable mode (--portable, or a cf-lvl.toml beside the executable) reads that file\n\
+            instead and keeps the cache and history in cf-lvl-cache/ and cf-lvl-data/ beside it.\n\
+          - An [alias] table maps a word to arguments, e.g. grind = \"pick --level 15 --random\";\n\
+            `cf-lvl grind` then runs those, followed by anything after the alias.\n\
+          - A [profile.<name>] table may set any of those keys (e.g. codeforces.handle,\n\
+            codeforces.name_scheme); --profile or CF_LVL_PROFILE picks it over the top level.\n\
+          - editor.command (or --editor) gets the starter file path in place of {path}, or\n\
+            appended when it has no {path}; it defaults to nvim.\n\
+          - browser.command (or --browser, CF_LVL_BROWSER) takes {url} the same way; unset, the\n\
+            system browser is used. With browser.open = false, or no DISPLAY/WAYLAND_DISPLAY on\n\
+            Linux, the URL is printed instead.\n\
+          - Arguments without a platform go to default_platform (or CF_LVL_PLATFORM), codeforces\n\
+            unless set; e.g. with atcoder, `cf-lvl c` picks an ABC C task.\n\
           - Codeforces default is level mode; provide level 8-32 (rating = level * 100), minimum 800.\n\
+            With no level at all, codeforces.level_offset (rating points, default +200) is added to\n\
+            your rating rounded to 100, kept within 800-3200.\n\
+          - A level with nothing left fails unless --fallback (or codeforces.fallback) allows\n\
+            100 or 200 either way; the nearest rating wins, the easier side first.\n\
           - Use --index (or -i) to select by Codeforces problem index letter (A, B, C, ...).\n\
-          - For AtCoder, provide the task letter (a, b, c, ...)."
-    );
+          - Default strategy per mode comes from CF_LVL_LEVEL_STRATEGY / CF_LVL_INDEX_STRATEGY\n\
+            (newest, oldest, random, variety or popular); strategy flags override it, and newest is used otherwise.\n\
+          - For AtCoder, provide the task letter (a, b, c, ...).\n\
+          - contests.include / contests.exclude tune which contests count: '|'-separated literal\n\
+            name fragments, '^'/'$' anchoring them. Excluded contests are never used; included\n\
+            ones without a division in the name count as Div. 2, e.g. exclude = \"Kotlin Heroes\".\n\
+          - April Fools and unrated rounds never count unless contests.include_special = true.\n\
+          - Div. 2 picks include Educational rounds and problems A-D of Div. 1 + Div. 2 rounds;\n\
+            --no-combined or contests.include_combined = false leaves the combined ones out.\n\
+          - A problem shared by a Div. 1 and a Div. 2 round (same name and rating) is offered\n\
+            once, and solving either copy counts for both.\n\
+          - --tier uses Div. 3/4 below 1400, Div. 2 for 1400-2099 and Div. 1, combined or\n\
+            global rounds from 2100; an explicit --division overrides it.\n\
+          - --refresh always wins over --max-age.\n\
+          - --format tokens are {name}, {rating}, {url}, {path}, {contest} and {index};\n\
+            \\t and \\n insert a tab and a newline.\n\
+          - --name-scheme tokens are {index}, {name}, {contest} and {rating} (default {name});\n\
+            .cpp is added unless the template ends in an extension.\n\
+          - --since/--before take a relative window (30d, 6m, 2y), a year (2021) or a date\n\
+            (2021-06-01), and filter contests by start time on both platforms.\n\
+          - Exit status: 0 success, 1 nothing matched, 2 bad arguments, 3 config error (missing\n\
+            or unknown handle, invalid setting, editor not found), 4 network or API failure, 5 anything\n\
+            else. Errors, including the Codeforces API's own reason for refusing a request, and\n\
+            no-match messages go to stderr."
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn global_flags_are_taken_from_any_position() {
+        let mut all_args = args(&[
+            "--verbose",
+            "14",
+            "--handle",
+            "tourist",
+            "--random",
+            "-q",
+            "--codeforces-handle",
+            "petr",
+            "--refresh",
+        ]);
+        let flags = take_global_flags(&mut all_args).unwrap();
+        assert_eq!(all_args, args(&["14", "--random"]));
+        assert!(flags.quiet && flags.verbose && flags.refresh);
+        assert!(!flags.json && !flags.timings);
+        // The platform-specific handle comes last, so it wins
+        let handles: Vec<(&str, &str)> = flags
+            .overrides
+            .iter()
+            .filter(|(key, _, _)| *key == "codeforces.handle")
+            .map(|(_, flag, value)| (*flag, value.as_str()))
+            .collect();
+        assert_eq!(
+            handles,
+            [("--handle", "tourist"), ("--codeforces-handle", "petr")]
+        );
+        assert!(matches!(
+            parse(all_args, Platform::Codeforces, flags.json),
+            Ok(Command::Codeforces {
+                mode: CfMode::Level(14),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn json_turns_a_listing_into_an_array() {
+        let listing = |json| match parse(args(&["14", "--list"]), Platform::Codeforces, json) {
+            Ok(Command::Codeforces { options, .. }) => options.listing,
+            other => panic!("expected a listing, got {other:?}"),
+        };
+        assert_eq!(listing(false), Some(cf::Listing::Text));
+        assert_eq!(listing(true), Some(cf::Listing::Json));
+    }
+
+    #[test]
+    fn short_flags_can_be_bundled() {
+        assert_eq!(
+            expand_short_flags(args(&["-qv", "-i", "C", "-qx"])),
+            args(&["-q", "-v", "-i", "C", "-qx"])
+        );
+    }
+
+    #[test]
+    fn unknown_options_are_rejected() {
+        let err = parse(args(&["14", "--randm"]), Platform::Codeforces, false).unwrap_err();
+        assert_eq!(err, "Unknown option '--randm'. See cf-lvl --help.");
+        let err = parse(args(&["man", "extra"]), Platform::Codeforces, false).unwrap_err();
+        assert_eq!(err, "Unexpected argument 'extra'. See cf-lvl --help.");
+    }
+
+    #[test]
+    fn skip_takes_a_codeforces_url_with_the_index_uppercased() {
+        let url = "https://codeforces.com/contest/1851/problem/c";
+        match parse(args(&["skip", url]), Platform::Codeforces, false) {
+            Ok(Command::Skip(SkipAction::Add(id))) => assert_eq!(id, "1851C"),
+            other => panic!("expected skip 1851C, got {other:?}"),
+        }
+        match parse(args(&["unskip", "1851c"]), Platform::Codeforces, false) {
+            Ok(Command::Skip(SkipAction::Remove(id))) => assert_eq!(id, "1851C"),
+            other => panic!("expected unskip 1851C, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn list_keeps_first_unsolved_a_listing() {
+        match parse(
+            args(&["list", "--first-unsolved"]),
+            Platform::Codeforces,
+            false,
+        ) {
+            Ok(Command::Codeforces {
+                mode: CfMode::FirstUnsolved,
+                options,
+            }) => {
+                assert_eq!(options.listing, Some(cf::Listing::Table));
+                assert_eq!(options.limit, Some(10));
+            }
+            other => panic!("expected a first-unsolved listing, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn subcommands_take_help() {
+        let page = |words: &[&str]| match parse(args(words), Platform::Codeforces, false) {
+            Ok(Command::HelpTopic(text)) => text.lines().next().unwrap_or_default().to_string(),
+            other => panic!("expected a help page for {words:?}, got {other:?}"),
+        };
+        assert!(page(&["dist", "--help"]).starts_with("cf-lvl dist"));
+        assert!(page(&["cf", "upsolve", "-h"]).starts_with("cf-lvl codeforces upsolve"));
+        assert!(page(&["atcoder", "d", "--help"]).starts_with("cf-lvl atcoder"));
+        assert!(page(&["config", "set", "-h"]).starts_with("cf-lvl init"));
+        assert!(page(&["14", "--random", "-h"]).starts_with("cf-lvl [level]"));
+        assert!(matches!(
+            parse(args(&["me", "--help"]), Platform::Codeforces, false),
+            Ok(Command::Help)
+        ));
+    }
+
+    #[test]
+    fn skip_rejects_atcoder_ids() {
+        let err = parse(args(&["skip", "abc250_d"]), Platform::Codeforces, false).unwrap_err();
+        assert_eq!(
+            err,
+            "Only Codeforces problems can be skipped, not 'abc250_d'."
+        );
+    }
 }
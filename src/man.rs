@@ -0,0 +1,70 @@
+use std::fmt::Write;
+
+/// Builds a roff manual page from the `--help` text, so the two never drift apart.
+pub fn page(usage: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        ".TH CF-LVL 1 \"{}\" \"cf-lvl {}\" \"User Commands\"",
+        env!("CF_LVL_BUILD_DATE"),
+        env!("CARGO_PKG_VERSION")
+    );
+    out.push_str(".SH NAME\ncf-lvl \\- pick unsolved Codeforces and AtCoder problems\n");
+    out.push_str(
+        ".SH SYNOPSIS\n.B cf-lvl\n[\\fIoptions\\fR] [\\fIcommand\\fR] [\\fIargs\\fR...]\n",
+    );
+    out.push_str(
+        ".SH DESCRIPTION\nPicks a problem you have not solved yet, creates its starter file, \
+         opens it in the browser and hands off to your editor.\n",
+    );
+
+    let mut section = None;
+    for line in usage.lines() {
+        let heading = match line {
+            "Usage:" => Some("COMMANDS"),
+            "Options:" => Some("OPTIONS"),
+            "Notes:" => Some("NOTES"),
+            _ => None,
+        };
+        if let Some(heading) = heading {
+            let _ = writeln!(out, ".SH {heading}");
+            section = Some(heading);
+            continue;
+        }
+        match section {
+            None => {}
+            Some("NOTES") => match line.strip_prefix("- ") {
+                Some(note) => {
+                    let _ = writeln!(out, ".IP \\(bu 2\n{}", escape(note));
+                }
+                None => {
+                    let _ = writeln!(out, "{}", escape(line.trim()));
+                }
+            },
+            Some(_) => match line.strip_prefix('#') {
+                // A description wrapped onto its own line
+                Some(more) => {
+                    let _ = writeln!(out, "{}", escape(more.trim()));
+                }
+                None => {
+                    let (term, description) = line.split_once(" #").unwrap_or((line, ""));
+                    let _ = writeln!(out, ".TP\n.B {}", escape(term.trim()));
+                    if !description.trim().is_empty() {
+                        let _ = writeln!(out, "{}", escape(description.trim()));
+                    }
+                }
+            },
+        }
+    }
+    out
+}
+
+/// Escapes backslashes, dashes and leading control characters for roff.
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{escaped}")
+    } else {
+        escaped
+    }
+}
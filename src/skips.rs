@@ -0,0 +1,86 @@
+use crate::utils::{data_dir, is_json, print_json};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const SKIP_FILE: &str = "skipped.txt";
+
+/// Codeforces problems never to pick, as ids such as `1851C`; a missing list is empty.
+pub fn read() -> io::Result<BTreeSet<String>> {
+    let Some(path) = skip_path() else {
+        return Ok(BTreeSet::new());
+    };
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(BTreeSet::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn write(ids: &BTreeSet<String>) -> io::Result<PathBuf> {
+    let path =
+        skip_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut contents = String::new();
+    for id in ids {
+        contents.push_str(id);
+        contents.push('\n');
+    }
+    fs::write(&path, contents)?;
+    Ok(path)
+}
+
+fn skip_path() -> Option<PathBuf> {
+    Some(data_dir()?.join(SKIP_FILE))
+}
+
+/// `skip` without an id: prints the list, one id per line.
+pub fn run_list() -> Result<(), Box<dyn Error>> {
+    let ids = read()?;
+    if is_json() {
+        return print_json(&ids);
+    }
+    if ids.is_empty() {
+        eprintln!("No problems are skipped.");
+    }
+    for id in ids {
+        println!("{id}");
+    }
+    Ok(())
+}
+
+/// `skip <id>`: keeps the problem out of every later pick.
+pub fn run_add(id: &str) -> Result<(), Box<dyn Error>> {
+    let mut ids = read()?;
+    if !ids.insert(id.to_string()) {
+        println!("{id} is already skipped.");
+        return Ok(());
+    }
+    let path = write(&ids)?;
+    println!(
+        "Skipped {id}; it will not be picked again ({}).",
+        path.display()
+    );
+    Ok(())
+}
+
+/// `unskip <id>`: lets the problem be picked again.
+pub fn run_remove(id: &str) -> Result<(), Box<dyn Error>> {
+    let mut ids = read()?;
+    if !ids.remove(id) {
+        println!("{id} was not skipped.");
+        return Ok(());
+    }
+    write(&ids)?;
+    println!("Unskipped {id}.");
+    Ok(())
+}
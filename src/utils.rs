@@ -1,5 +1,19 @@
+use crate::cache;
+use crate::config;
+use crate::error::CfLvlError;
 use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::env;
 use std::error::Error;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 pub fn build_client() -> Result<Client, Box<dyn Error>> {
     let mut headers = reqwest::header::HeaderMap::new();
@@ -24,3 +38,789 @@ pub fn build_client() -> Result<Client, Box<dyn Error>> {
 
     Ok(Client::builder().default_headers(headers).build()?)
 }
+
+static REQUEST_COUNT: AtomicUsize = AtomicUsize::new(0);
+static REQUEST_LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Caps the number of HTTP requests this process may make.
+pub fn set_request_limit(limit: usize) {
+    REQUEST_LIMIT.store(limit, Ordering::Relaxed);
+}
+
+/// Issues a GET request and decodes the JSON body, reporting non-success statuses
+/// together with the start of the response body.
+pub fn fetch_json<T: DeserializeOwned>(client: &Client, url: &str) -> Result<T, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fetch_text(client, url)?)?)
+}
+
+/// Like `fetch_json`, but answers from the response cache when the entry is fresh.
+/// Meant for slowly changing catalogue endpoints, not per-user data.
+pub fn fetch_json_cached<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+) -> Result<T, Box<dyn Error>> {
+    if let Some(body) = cache::get(url) {
+        if let Ok(value) = serde_json::from_str(&body) {
+            return Ok(value);
+        }
+    }
+
+    let body = fetch_text(client, url)?;
+    let value = serde_json::from_str(&body)?;
+    cache::put(url, &body);
+    Ok(value)
+}
+
+fn fetch_text(client: &Client, url: &str) -> Result<String, Box<dyn Error>> {
+    count_request()?;
+    let response = client.get(url).send()?;
+    let status = response.status();
+    let body = response.text()?;
+    if let Some(comment) = api_failure(&body) {
+        return Err(CfLvlError::Api { comment }.into());
+    }
+    if !status.is_success() {
+        return Err(CfLvlError::http(url, status.as_u16(), &body).into());
+    }
+    Ok(body)
+}
+
+/// The envelope every Codeforces API answer comes in.
+#[derive(Deserialize)]
+struct ApiStatus {
+    status: String,
+    comment: Option<String>,
+}
+
+/// The API's own explanation when `body` is a Codeforces `FAILED` answer, which
+/// arrives with HTTP 400 or, now and then, 200.
+fn api_failure(body: &str) -> Option<String> {
+    // Only look closer at bodies that start like a failure; the problemset is large
+    if !body.trim_start().starts_with(r#"{"status":"FAILED""#) {
+        return None;
+    }
+    let envelope: ApiStatus = serde_json::from_str(body).ok()?;
+    (envelope.status == "FAILED").then(|| {
+        envelope
+            .comment
+            .unwrap_or_else(|| "no reason given".to_string())
+    })
+}
+
+/// Issues a GET request and returns its status code, whatever it is; only
+/// connection failures are errors.
+pub fn fetch_status(client: &Client, url: &str) -> Result<u16, Box<dyn Error>> {
+    count_request()?;
+    Ok(client.get(url).send()?.status().as_u16())
+}
+
+fn count_request() -> Result<(), CfLvlError> {
+    let limit = REQUEST_LIMIT.load(Ordering::Relaxed);
+    if REQUEST_COUNT.fetch_add(1, Ordering::Relaxed) >= limit {
+        return Err(CfLvlError::RequestLimit { limit });
+    }
+    Ok(())
+}
+
+/// Parses an age such as `90s`, `30m`, `2h` or `1d`.
+pub fn parse_duration(spec: &str) -> Result<Duration, String> {
+    let spec = spec.trim();
+    let invalid = || format!("Invalid duration '{spec}'. Use e.g. 90s, 30m, 2h or 1d.");
+    let unit = spec.chars().last().ok_or_else(invalid)?;
+    let amount: u64 = spec[..spec.len() - unit.len_utf8()]
+        .parse()
+        .map_err(|_| invalid())?;
+    let seconds = match unit.to_ascii_lowercase() {
+        's' => amount,
+        'm' => amount * 60,
+        'h' => amount * 60 * 60,
+        'd' => amount * SECONDS_PER_DAY,
+        _ => return Err(invalid()),
+    };
+    Ok(Duration::from_secs(seconds))
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Parses a contest time bound into a Unix timestamp.
+///
+/// Accepts either a window relative to now (`30d`, `8w`, `6m`, `2y`, where a month is
+/// 30 days and a year 365 days) or an absolute date (`2021`, `2021-06` or `2021-06-01`).
+pub fn parse_time_bound(spec: &str) -> Result<u64, String> {
+    let spec = spec.trim();
+    let invalid = || {
+        format!(
+            "Invalid time bound '{spec}'. Use a relative window like 30d, 6m, 2y or a date like 2021-06-01."
+        )
+    };
+
+    if let Some(unit) = spec.chars().last().filter(|c| c.is_ascii_alphabetic()) {
+        let amount: u64 = spec[..spec.len() - 1].parse().map_err(|_| invalid())?;
+        let days = match unit.to_ascii_lowercase() {
+            'd' => amount,
+            'w' => amount * 7,
+            'm' => amount * 30,
+            'y' => amount * 365,
+            _ => return Err(invalid()),
+        };
+        return Ok(now_epoch().saturating_sub(days * SECONDS_PER_DAY));
+    }
+
+    let mut parts = spec.split('-');
+    let year: i64 = parts
+        .next()
+        .and_then(|y| y.parse().ok())
+        .ok_or_else(invalid)?;
+    let month: u32 = match parts.next() {
+        Some(m) => m.parse().map_err(|_| invalid())?,
+        None => 1,
+    };
+    let day: u32 = match parts.next() {
+        Some(d) => d.parse().map_err(|_| invalid())?,
+        None => 1,
+    };
+    if parts.next().is_some() || !(1970..=9999).contains(&year) {
+        return Err(invalid());
+    }
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return Err(invalid());
+    }
+
+    Ok(days_from_civil(year, month, day) as u64 * SECONDS_PER_DAY)
+}
+
+/// Restricts candidates to contests that started within `[since, before)`, as Unix timestamps.
+#[derive(Debug, Default)]
+pub struct ContestWindow {
+    pub since: Option<u64>,
+    pub before: Option<u64>,
+}
+
+impl ContestWindow {
+    pub fn contains(&self, start: u64) -> bool {
+        self.since.is_none_or(|since| start >= since)
+            && self.before.is_none_or(|before| start < before)
+    }
+
+    pub fn is_unbounded(&self) -> bool {
+        self.since.is_none() && self.before.is_none()
+    }
+
+    /// The bounds as dates, e.g. `since 2021-01-01`; `None` when unbounded.
+    pub fn describe(&self) -> Option<String> {
+        let mut parts = Vec::new();
+        if let Some(since) = self.since {
+            parts.push(format!("since {}", format_date(since)));
+        }
+        if let Some(before) = self.before {
+            parts.push(format!("before {}", format_date(before)));
+        }
+        (!parts.is_empty()).then(|| parts.join(", "))
+    }
+}
+
+/// Today's date in UTC as `YYYY-MM-DD`.
+pub fn today_utc() -> String {
+    format_date(now_epoch())
+}
+
+/// The UTC date of a Unix timestamp as `YYYY-MM-DD`.
+pub fn format_date(epoch: u64) -> String {
+    let (year, month, day) = civil_from_days((epoch / SECONDS_PER_DAY) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+pub fn now_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+// Inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Prints a `Warning:` line on stderr unless `--quiet` is set.
+pub fn warn(message: fmt::Arguments) {
+    if !is_quiet() {
+        eprintln!("Warning: {message}");
+    }
+}
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+/// Switches every command to printing one JSON document on stdout.
+pub fn set_json(json: bool) {
+    JSON.store(json, Ordering::Relaxed);
+}
+
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Prints `value` as pretty JSON on stdout.
+pub fn print_json<T: Serialize>(value: &T) -> Result<(), Box<dyn Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Directory for disposable API responses.
+pub fn cache_dir() -> Option<PathBuf> {
+    app_dir(AppDir::Cache)
+}
+
+/// Directory holding `config.toml`.
+pub fn config_dir() -> Option<PathBuf> {
+    app_dir(AppDir::Config)
+}
+
+/// Directory for data worth keeping, such as the pick history.
+pub fn data_dir() -> Option<PathBuf> {
+    app_dir(AppDir::Data)
+}
+
+static PORTABLE: AtomicBool = AtomicBool::new(false);
+
+/// Config file that, next to the executable, turns on portable mode.
+pub const PORTABLE_CONFIG_FILE: &str = "cf-lvl.toml";
+
+pub fn set_portable(portable: bool) {
+    PORTABLE.store(portable, Ordering::Relaxed);
+}
+
+/// The executable's directory when running portably, that is with `--portable`
+/// or with a `cf-lvl.toml` beside the binary.
+pub fn portable_dir() -> Option<PathBuf> {
+    let dir = env::current_exe().ok()?.parent()?.to_path_buf();
+    (PORTABLE.load(Ordering::Relaxed) || dir.join(PORTABLE_CONFIG_FILE).exists()).then_some(dir)
+}
+
+#[derive(Clone, Copy)]
+enum AppDir {
+    Config,
+    Cache,
+    Data,
+}
+
+/// Resolves a per-user directory for cf-lvl the way each platform expects:
+/// XDG (`~/.config`, `~/.cache`, `~/.local/share`) on Linux and other Unixes,
+/// `~/Library/Application Support` and `~/Library/Caches` on macOS, and
+/// `%APPDATA%`/`%LOCALAPPDATA%` on Windows. A set `$XDG_*_HOME` wins everywhere
+/// except in portable mode, where everything lives beside the executable.
+fn app_dir(kind: AppDir) -> Option<PathBuf> {
+    if let Some(dir) = portable_dir() {
+        return Some(match kind {
+            AppDir::Config => dir,
+            AppDir::Cache => dir.join("cf-lvl-cache"),
+            AppDir::Data => dir.join("cf-lvl-data"),
+        });
+    }
+    let (xdg_var, xdg_default, windows_leaf) = match kind {
+        AppDir::Config => ("XDG_CONFIG_HOME", ".config", "config"),
+        AppDir::Cache => ("XDG_CACHE_HOME", ".cache", "cache"),
+        AppDir::Data => ("XDG_DATA_HOME", ".local/share", "data"),
+    };
+    if let Some(base) = env_path(xdg_var) {
+        return Some(base.join(APP_DIR_NAME));
+    }
+
+    if cfg!(windows) {
+        let base = match kind {
+            AppDir::Cache => env_path("LOCALAPPDATA")?,
+            AppDir::Config | AppDir::Data => env_path("APPDATA")?,
+        };
+        return Some(base.join(APP_DIR_NAME).join(windows_leaf));
+    }
+
+    let home = env_path("HOME")?;
+    if cfg!(target_os = "macos") {
+        let base = match kind {
+            AppDir::Cache => home.join("Library/Caches"),
+            AppDir::Config | AppDir::Data => home.join("Library/Application Support"),
+        };
+        return Some(base.join(APP_DIR_NAME));
+    }
+    Some(home.join(xdg_default).join(APP_DIR_NAME))
+}
+
+/// Columns to lay output out in: `COLUMNS` when the shell exports it, else 80.
+pub fn terminal_width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|&columns: &usize| columns > 0)
+        .unwrap_or(80)
+}
+
+/// Reads a non-empty path from the environment.
+pub fn env_path(key: &str) -> Option<PathBuf> {
+    env::var_os(key)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
+
+const APP_DIR_NAME: &str = "cf-lvl";
+
+static TIMINGS_ENABLED: AtomicBool = AtomicBool::new(false);
+static TIMINGS: Mutex<Vec<(String, Duration)>> = Mutex::new(Vec::new());
+
+pub fn set_timings(enabled: bool) {
+    TIMINGS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Prints and clears the recorded fetch timings on stderr, if `--timings` is set.
+pub fn report_timings() {
+    if !TIMINGS_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let timings = std::mem::take(&mut *TIMINGS.lock().unwrap_or_else(|e| e.into_inner()));
+    if timings.is_empty() {
+        return;
+    }
+
+    let width = timings
+        .iter()
+        .map(|(label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    eprintln!("Timings:");
+    let mut total = Duration::ZERO;
+    for (label, elapsed) in &timings {
+        eprintln!("  {label:width$}  {:>8.3}s", elapsed.as_secs_f64());
+        total += *elapsed;
+    }
+    eprintln!("  {:width$}  {:>8.3}s", "total", total.as_secs_f64());
+}
+
+/// A status line on stderr shown while a fetch is in flight and cleared when dropped.
+///
+/// The line is skipped when stderr is not a terminal or `--quiet` is set, so stdout
+/// and redirected logs are never touched. Each spinner also records how long its
+/// step ran for `--timings`, including steps that end in an error.
+pub struct Spinner {
+    label: String,
+    started: Instant,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Spinner {
+    pub fn start(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let label = message.trim_end_matches('…').to_string();
+        let started = Instant::now();
+        let stop = Arc::new(AtomicBool::new(false));
+        if is_quiet() || !io::stderr().is_terminal() {
+            return Spinner {
+                label,
+                started,
+                stop,
+                handle: None,
+            };
+        }
+
+        let flag = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let frames = ['|', '/', '-', '\\'];
+            let mut frame = 0;
+            while !flag.load(Ordering::Relaxed) {
+                eprint!("\r{} {}", frames[frame % frames.len()], message);
+                let _ = io::stderr().flush();
+                frame += 1;
+                thread::sleep(Duration::from_millis(100));
+            }
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+        });
+
+        Spinner {
+            label,
+            started,
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+        if TIMINGS_ENABLED.load(Ordering::Relaxed) {
+            let entry = (std::mem::take(&mut self.label), self.started.elapsed());
+            TIMINGS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(entry);
+        }
+    }
+}
+
+/// SplitMix64 generator; plenty for picking problems, not for anything secret.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn seeded(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    /// Seeds from a string with FNV-1a, so equal keys give equal sequences.
+    pub fn from_key(key: &str) -> Self {
+        let hash = key.bytes().fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3)
+        });
+        Rng(hash)
+    }
+
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Rng(nanos ^ u64::from(std::process::id()).rotate_left(32))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a value in `0..n`; `n` must be non-zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+/// Makes a problem name usable as a file name; empty names become `problem`.
+pub fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c == '/' || c == '\\' {
+                '-'
+            } else if c.is_control() {
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+
+    let trimmed = cleaned.trim();
+    if trimmed.is_empty() {
+        "problem".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Tokens understood by `--format`.
+pub const FORMAT_TOKENS: [&str; 6] = [
+    "{name}",
+    "{rating}",
+    "{url}",
+    "{path}",
+    "{contest}",
+    "{index}",
+];
+
+/// Validates a `--format` template and expands its `\t`, `\n` and `\\` escapes.
+pub fn parse_format(template: &str) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            expanded.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => expanded.push('\t'),
+            Some('n') => expanded.push('\n'),
+            Some('\\') => expanded.push('\\'),
+            Some(other) => return Err(format!("Unknown escape '\\{other}' in --format.")),
+            None => return Err("--format ends with a lone backslash.".to_string()),
+        }
+    }
+
+    let mut rest = expanded.clone();
+    for token in FORMAT_TOKENS {
+        rest = rest.replace(token, "");
+    }
+    if let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .map_or(rest.len(), |i| start + i + 1);
+        return Err(format!(
+            "Unknown --format token '{}'. Use {}.",
+            &rest[start..end],
+            FORMAT_TOKENS.join(", ")
+        ));
+    }
+    Ok(expanded)
+}
+
+/// Fills a template from `parse_format` with `(token, value)` pairs; tokens
+/// without a value become empty.
+pub fn render_format(template: &str, values: &[(&str, String)]) -> String {
+    let mut rendered = template.to_string();
+    for token in FORMAT_TOKENS {
+        let value = values
+            .iter()
+            .find(|(name, _)| *name == token)
+            .map_or("", |(_, value)| value.as_str());
+        rendered = rendered.replace(token, value);
+    }
+    rendered
+}
+
+/// Splits a command template into words, putting `value` where `token` appears
+/// or appending it when the template has no `token`.
+pub fn expand_command(template: &str, token: &str, value: &str) -> Vec<String> {
+    let mut words: Vec<String> = template.split_whitespace().map(str::to_string).collect();
+    if words.iter().any(|word| word.contains(token)) {
+        for word in &mut words {
+            *word = word.replace(token, value);
+        }
+    } else {
+        words.push(value.to_string());
+    }
+    words
+}
+
+/// Hands `url` to `browser.command`, or the system browser when that is unset.
+/// Returns false, without launching anything, when `browser.open` is false or
+/// there is no display to show a browser on; callers then print the URL.
+pub fn open_url(url: &str) -> bool {
+    let settings = config::settings().ok();
+    if settings.is_some_and(|settings| !settings.browser_enabled()) {
+        return false;
+    }
+    match settings.map(|settings| settings.browser.value.trim()) {
+        Some(command) if !command.is_empty() => {
+            let words = expand_command(command, "{url}", url);
+            let spawned = std::process::Command::new(&words[0])
+                .args(&words[1..])
+                .stdin(std::process::Stdio::null())
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .spawn();
+            match spawned {
+                Ok(_) => true,
+                Err(err) => {
+                    warn(format_args!(
+                        "could not run browser.command '{command}': {err}"
+                    ));
+                    false
+                }
+            }
+        }
+        _ => has_display() && webbrowser::open(url).is_ok(),
+    }
+}
+
+/// Asks `question` on stderr until it gets a number from 1 to `count`, returning
+/// its zero-based position; `None` for q, an empty answer or end of input.
+pub fn ask_choice(question: &str, count: usize) -> Result<Option<usize>, Box<dyn Error>> {
+    loop {
+        eprint!("{question} [1-{count}, q]: ");
+        io::stderr().flush()?;
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            eprintln!();
+            return Ok(None);
+        }
+        match line.trim() {
+            "" | "q" | "Q" => return Ok(None),
+            answer => match answer.parse::<usize>() {
+                Ok(n) if (1..=count).contains(&n) => return Ok(Some(n - 1)),
+                _ => eprintln!("Answer a number from 1 to {count}, or q to stop."),
+            },
+        }
+    }
+}
+
+/// On X11/Wayland systems a browser needs `DISPLAY` or `WAYLAND_DISPLAY`;
+/// macOS and Windows always have one.
+fn has_display() -> bool {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        return true;
+    }
+    ["DISPLAY", "WAYLAND_DISPLAY"]
+        .iter()
+        .any(|key| env::var_os(key).is_some_and(|value| !value.is_empty()))
+}
+
+/// Asks `question` on stderr for `--confirm`: `Some(true)` for y, `Some(false)`
+/// for n, `None` for q or end of input.
+pub fn ask_accept(question: &str) -> Result<Option<bool>, Box<dyn Error>> {
+    loop {
+        eprint!("{question} [y/n/q]: ");
+        io::stderr().flush()?;
+        let mut line = String::new();
+        if io::stdin().read_line(&mut line)? == 0 {
+            eprintln!();
+            return Ok(None);
+        }
+        match line.trim().to_ascii_lowercase().as_str() {
+            "y" | "yes" => return Ok(Some(true)),
+            "n" | "no" => return Ok(Some(false)),
+            "q" | "quit" => return Ok(None),
+            _ => eprintln!("Answer y to accept, n for the next candidate, or q to stop."),
+        }
+    }
+}
+
+/// Writes `contents` to `path` unless the file already exists, creating parent
+/// directories. Returns whether a file was created.
+pub fn write_starter(path: &Path, contents: &str) -> Result<bool, Box<dyn Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    if path.exists() {
+        return Ok(false);
+    }
+
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    file.write_all(contents.as_bytes())?;
+    Ok(true)
+}
+
+/// Shortest readable form of `p`: relative to the working directory, then
+/// `~`-relative, then absolute.
+pub fn display_path(p: &Path) -> String {
+    // 1. Try relative path from CWD
+    if let Ok(cwd) = env::current_dir() {
+        if let Some(diff) = diff_paths(p, &cwd) {
+            return diff.display().to_string();
+        }
+    }
+
+    // 2. Try ~ replacement
+    if let Ok(home) = env::var("HOME") {
+        let home_path = Path::new(&home);
+        if let Ok(stripped) = p.strip_prefix(home_path) {
+            return format!("~/{}", stripped.display());
+        }
+    }
+
+    // 3. Fallback to absolute
+    p.display().to_string()
+}
+
+fn diff_paths(path: &Path, base: &Path) -> Option<PathBuf> {
+    let path_comps: Vec<_> = path.components().collect();
+    let base_comps: Vec<_> = base.components().collect();
+
+    let mut i = 0;
+    while i < path_comps.len() && i < base_comps.len() && path_comps[i] == base_comps[i] {
+        i += 1;
+    }
+
+    if i == 0 {
+        return None;
+    }
+
+    let mut new_path = PathBuf::new();
+    for _ in i..base_comps.len() {
+        new_path.push("..");
+    }
+    for component in &path_comps[i..] {
+        new_path.push(component);
+    }
+
+    Some(new_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{exit_code, EXIT_NETWORK};
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    /// Answers one request on a local port with `status` and `body`, returning its URL.
+    fn serve_once(status: &str, body: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/api/problemset.problems",
+            listener.local_addr().unwrap()
+        );
+        let status = status.to_string();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request);
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+        });
+        url
+    }
+
+    #[test]
+    fn http_errors_carry_status_url_and_a_short_body() {
+        let url = serve_once("503 Service Unavailable", "x".repeat(300));
+        let client = Client::builder().no_proxy().build().unwrap();
+
+        let err = fetch_text(&client, &url).unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("HTTP 503"), "{message}");
+        assert!(message.contains(&url), "{message}");
+        match err.downcast_ref::<CfLvlError>() {
+            Some(CfLvlError::Http { status, body, .. }) => {
+                assert_eq!(*status, 503);
+                assert_eq!(body.strip_suffix('…'), Some("x".repeat(200).as_str()));
+            }
+            other => panic!("expected an HTTP error, got {other:?}"),
+        }
+        assert_eq!(exit_code(err.as_ref()), EXIT_NETWORK);
+    }
+}